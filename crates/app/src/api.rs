@@ -0,0 +1,367 @@
+//! Local HTTP/JSON API for overlays and scripts, e.g. `curl http://127.0.0.1:7070/matches`
+
+use std::{
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use common::stats;
+use egui::mutex::RwLock;
+use serde::{Deserialize, Serialize};
+use server::courier::Courier;
+use snafu::ResultExt;
+use tracing::{error, info};
+
+use crate::{
+    archive::{MatchArchive, Views},
+    constant::GameConstant,
+    error::ServeSnafu,
+    notes::{MatchNote, NoteBook},
+    overlay::{OverlaySummary, DEFAULT_OVERLAY_RESULTS},
+    persist::Persistence,
+    state::AppState,
+};
+
+pub const DEFAULT_PORT: u16 = 7070;
+/// Minutes between fetches of the latest match when auto-refresh is off but something shows live
+/// data, i.e. the overlay window or the local API
+pub const FALLBACK_REFRESH_INTERVAL: u32 = 2;
+/// `--serve [port]` serves the API without opening the window
+pub const SERVE_FLAG: &str = "--serve";
+
+/// Port after `--serve`, `DEFAULT_PORT` when it is missing or not a port
+pub fn port_from_args(args: &[String]) -> Option<u16> {
+    let index = args.iter().position(|arg| arg == SERVE_FLAG)?;
+    Some(args.get(index + 1).and_then(|port| port.parse().ok()).unwrap_or(DEFAULT_PORT))
+}
+
+/// Shared with the GUI, every request reads the latest data
+#[derive(Clone)]
+pub struct ApiState {
+    pub archive: Arc<RwLock<MatchArchive>>,
+    pub constant: Arc<RwLock<GameConstant>>,
+    pub notes: Arc<RwLock<NoteBook>>,
+    /// The tracked account, kept in sync with `AppState::account_id`
+    pub account_id: Arc<AtomicI64>,
+}
+
+impl ApiState {
+    fn views(&self) -> Views {
+        let account_id = self.account_id.load(Ordering::Relaxed);
+        self.archive.read().views(account_id)
+    }
+
+    fn overlay_summary(
+        &self,
+        results: usize,
+    ) -> OverlaySummary {
+        let views = self.views();
+        OverlaySummary::new(views.iter(), &self.constant.read(), results, chrono::Local::now().date_naive())
+    }
+}
+
+/// Serve the cached `config/` data until Ctrl+C, fetching the latest match every
+/// `refresh_interval` minutes, or `FALLBACK_REFRESH_INTERVAL` when auto-refresh is off
+pub fn serve_headless(port: u16) -> Result<(), crate::Error> {
+    let state = AppState::try_from_config().unwrap_or_default();
+    let api_state = ApiState {
+        archive: Arc::new(RwLock::new(MatchArchive::from_config().unwrap_or_default())),
+        constant: Arc::new(RwLock::new(GameConstant::from_config().unwrap_or_default())),
+        notes: Arc::new(RwLock::new(NoteBook::from_config().unwrap_or_default())),
+        account_id: Arc::new(AtomicI64::new(state.account_id)),
+    };
+    let mut persistence = Persistence::new(
+        &state,
+        Arc::clone(&api_state.constant),
+        Arc::clone(&api_state.archive),
+        Arc::clone(&api_state.notes),
+    );
+    let rt = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+    let res = rt.block_on(async {
+        tokio::select! {
+            res = serve(api_state.clone(), port) => res,
+            _ = poll_latest_match(&state, &api_state.archive, &mut persistence) => Ok(()),
+            _ = tokio::signal::ctrl_c() => {
+                info!("Ctrl+C received, stop serving");
+                Ok(())
+            }
+        }
+    });
+    persistence.flush(&state);
+    res
+}
+
+/// Archive the latest match periodically, never returns
+async fn poll_latest_match(
+    state: &AppState,
+    archive: &RwLock<MatchArchive>,
+    persistence: &mut Persistence,
+) {
+    let courier = Courier::with_steam_api(state.settings.steam_api());
+    let minutes = match state.settings.refresh_interval {
+        0 => FALLBACK_REFRESH_INTERVAL,
+        minutes => minutes,
+    };
+    let mut interval = tokio::time::interval(Duration::from_secs(u64::from(minutes) * 60));
+    loop {
+        interval.tick().await;
+        match courier.latest_match_detail(&state.steam_api_key, state.account_id).await {
+            Ok(matches) => {
+                let inserted = archive.write().insert(matches);
+                if inserted > 0 {
+                    info!("Archived {} new matches", inserted);
+                    persistence.save(state);
+                }
+            }
+            Err(e) => error!("Failed to get match detail: {}", e),
+        }
+    }
+}
+
+/// Serve on `127.0.0.1:port` until the task is aborted
+pub async fn serve(
+    state: ApiState,
+    port: u16,
+) -> Result<(), crate::Error> {
+    let address = format!("127.0.0.1:{}", port);
+    let listener = tokio::net::TcpListener::bind(&address).await.context(ServeSnafu { address: &address })?;
+    info!("Serving the local API on http://{}", address);
+    axum::serve(listener, router(state)).await.context(ServeSnafu { address })
+}
+
+fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/matches", get(matches))
+        .route("/matches/{id}", get(match_detail))
+        .route("/heroes/stats", get(hero_stats))
+        .route("/constants", get(constants))
+        .route("/overlay", get(overlay))
+        .route("/overlay.json", get(overlay_json))
+        .with_state(state)
+}
+
+/// One row of `/matches`, from the tracked account's point of view
+#[derive(Serialize)]
+struct MatchSummary {
+    match_id: i64,
+    start_time: i64,
+    duration: i32,
+    game_mode: String,
+    win: bool,
+    hero_id: i32,
+    hero: String,
+    kills: i32,
+    deaths: i32,
+    assists: i32,
+    gold_per_min: i32,
+    net_worth: i32,
+    /// The user's notes, tags and rating
+    note: Option<MatchNote>,
+}
+
+#[derive(Serialize)]
+struct HeroStatsBody {
+    #[serde(flatten)]
+    stats: stats::HeroStats,
+    hero: String,
+    win_rate: f32,
+    kda: f32,
+    /// Win rate per facet, keyed by `hero_variant`
+    facet_stats: Vec<FacetStatsBody>,
+}
+
+#[derive(Serialize)]
+struct FacetStatsBody {
+    variant: i32,
+    facet: String,
+    record: stats::Record,
+    win_rate: f32,
+}
+
+/// `?offset=N&limit=M` pages through `/matches`, every match by default
+#[derive(Deserialize)]
+struct MatchesQuery {
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+}
+
+/// Tracked account's matches, newest first
+async fn matches(
+    State(state): State<ApiState>,
+    Query(query): Query<MatchesQuery>,
+) -> Json<Vec<MatchSummary>> {
+    let views = state.views();
+    let constant = state.constant.read();
+    let notes = state.notes.read();
+    let summaries = views
+        .iter()
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .map(|view| {
+            let player = view.player_detail();
+            MatchSummary {
+                match_id: view.match_id(),
+                start_time: view.start_time(),
+                duration: view.duration(),
+                game_mode: view.game_mode_col(),
+                win: view.win(),
+                hero_id: player.hero_id,
+                hero: constant.hero(player.hero_id).to_string(),
+                kills: player.kills,
+                deaths: player.deaths,
+                assists: player.assists,
+                gold_per_min: player.gold_per_min,
+                net_worth: player.net_worth,
+                note: notes.get(view.match_id()).cloned(),
+            }
+        })
+        .collect();
+    Json(summaries)
+}
+
+/// Any archived match, raw as returned by the Steam API
+async fn match_detail(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.archive.read().get(id) {
+        Some(detail) => Json(detail).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("Match {} is not archived", id)).into_response(),
+    }
+}
+
+async fn hero_stats(State(state): State<ApiState>) -> Json<Vec<HeroStatsBody>> {
+    let views = state.views();
+    let constant = state.constant.read();
+    let body = stats::hero_stats(views.iter())
+        .into_iter()
+        .map(|stats| HeroStatsBody {
+            hero: constant.hero(stats.hero_id).to_string(),
+            win_rate: stats.win_rate(),
+            kda: stats.kda(),
+            facet_stats: stats
+                .facets
+                .iter()
+                .map(|(variant, record)| FacetStatsBody {
+                    variant: *variant,
+                    facet: constant.facet_name(stats.hero_id, *variant).to_string(),
+                    record: *record,
+                    win_rate: record.win_rate(),
+                })
+                .collect(),
+            stats,
+        })
+        .collect();
+    Json(body)
+}
+
+/// `?results=N` pips on the overlay
+#[derive(Deserialize)]
+struct OverlayQuery {
+    results: Option<usize>,
+}
+
+impl OverlayQuery {
+    fn results(&self) -> usize {
+        self.results.unwrap_or(DEFAULT_OVERLAY_RESULTS)
+    }
+}
+
+/// HTML page for OBS browser sources, refreshes itself when a new match shows up
+async fn overlay(
+    State(state): State<ApiState>,
+    Query(query): Query<OverlayQuery>,
+) -> Html<String> {
+    Html(state.overlay_summary(query.results()).to_html())
+}
+
+async fn overlay_json(
+    State(state): State<ApiState>,
+    Query(query): Query<OverlayQuery>,
+) -> Json<OverlaySummary> {
+    Json(state.overlay_summary(query.results()))
+}
+
+async fn constants(State(state): State<ApiState>) -> Response {
+    Json(state.constant.read().names()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+    };
+    use common::fixture;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    /// Matches 1 to 3 of account 1
+    fn state() -> ApiState {
+        let mut archive = MatchArchive::default();
+        archive.insert(
+            (1..=3)
+                .map(|id| {
+                    let fields = serde_json::json!({"match_id": id, "match_seq_num": id, "start_time": id});
+                    fixture::match_detail(vec![fixture::player_json(serde_json::json!({}))], fields)
+                })
+                .collect(),
+        );
+        ApiState {
+            archive: Arc::new(RwLock::new(archive)),
+            constant: Arc::new(RwLock::new(GameConstant::default())),
+            notes: Arc::new(RwLock::new(NoteBook::default())),
+            account_id: Arc::new(AtomicI64::new(1)),
+        }
+    }
+
+    async fn get(uri: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = router(state()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn match_by_id() {
+        let (status, body) = get("/matches/2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["match_id"], 2);
+        assert_eq!(get("/matches/4").await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn paginate_matches() {
+        let ids = |body: serde_json::Value| {
+            body.as_array()
+                .unwrap()
+                .iter()
+                .map(|m| m["match_id"].as_i64().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(get("/matches").await.1), [3, 2, 1]);
+        assert_eq!(ids(get("/matches?offset=1&limit=1").await.1), [2]);
+        assert_eq!(ids(get("/matches?offset=5").await.1), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn serve_flag() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(port_from_args(&args(&["--serve", "8080"])), Some(8080));
+        assert_eq!(port_from_args(&args(&["--serve"])), Some(DEFAULT_PORT));
+        assert_eq!(port_from_args(&args(&["--match", "7"])), None);
+    }
+}
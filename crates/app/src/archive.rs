@@ -0,0 +1,133 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use common::data::matches::{MatchDetail, MatchDetailView};
+use snafu::ResultExt;
+
+use crate::{
+    error::{JsonSnafu, ReadFileSnafu},
+    persist::{PendingWrite, Store},
+};
+
+/// Views of the matches one account played in, newest first, shared instead of cloned
+pub type Views = Arc<VecDeque<MatchDetailView>>;
+
+/// Every match fetched so far, keyed by `match_id`, so the history survives restarts and only new
+/// pages have to be requested from the Steam API
+#[derive(Default)]
+pub struct MatchArchive {
+    matches: BTreeMap<i64, MatchDetail>,
+    /// Bumped on every change, including a re-fetched match replacing an archived one
+    generation: u64,
+    /// `views` of the last account asked for, with the generation they were built at
+    views: Mutex<Option<(i64, u64, Views)>>,
+}
+
+impl MatchArchive {
+    pub fn from_config() -> Result<Self, crate::Error> {
+        let json = std::fs::read_to_string("config/matches.json").context(ReadFileSnafu {
+            filename: "config/matches.json",
+        })?;
+        let matches: Vec<MatchDetail> = serde_json::from_str(&json).context(JsonSnafu)?;
+
+        let mut archive = Self::default();
+        archive.insert(matches);
+        Ok(archive)
+    }
+
+    /// Returns how many of `matches` were not archived before
+    pub fn insert(
+        &mut self,
+        matches: Vec<MatchDetail>,
+    ) -> usize {
+        let mut inserted = 0;
+        for m in matches {
+            self.generation += 1;
+            if self.matches.insert(m.match_id(), m).is_none() {
+                inserted += 1;
+            }
+        }
+        inserted
+    }
+
+    pub fn get(
+        &self,
+        match_id: i64,
+    ) -> Option<&MatchDetail> {
+        self.matches.get(&match_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// Changes whenever a match is inserted or replaced, for caches built from the archive
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Views of the matches `account_id` played in, newest first. The others are in `spectated`.
+    /// Built once per generation and account, later calls share the same list.
+    pub fn views(
+        &self,
+        account_id: i64,
+    ) -> Views {
+        let mut cached = self.views.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((id, generation, views)) = cached.as_ref() {
+            if *id == account_id && *generation == self.generation {
+                return Arc::clone(views);
+            }
+        }
+        let views: Views = Arc::new(
+            self.matches
+                .values()
+                .rev()
+                .filter_map(|m| MatchDetailView::from_match_detail(m.clone(), account_id).ok())
+                .collect(),
+        );
+        *cached = Some((account_id, self.generation, Arc::clone(&views)));
+        views
+    }
+
+    /// Matches `account_id` didn't play in, newest first
+    pub fn spectated(
+        &self,
+        account_id: i64,
+    ) -> Vec<MatchDetail> {
+        self.matches
+            .values()
+            .rev()
+            .filter(|m| m.player_index_of(account_id).is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// Oldest archived match `account_id` played in, used as the cursor for the next history page
+    pub fn oldest_match_id(
+        &self,
+        account_id: i64,
+    ) -> Option<i64> {
+        self.matches
+            .values()
+            .find(|m| m.players().iter().any(|p| p.account_id == account_id))
+            .map(|m| m.match_id())
+    }
+}
+
+impl Store for MatchArchive {
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn files(&self) -> Result<Vec<PendingWrite>, crate::Error> {
+        let matches = self.matches.values().collect::<Vec<_>>();
+        let json = serde_json::to_string(&matches).context(JsonSnafu)?;
+        Ok(vec![PendingWrite {
+            path: "config/matches.json",
+            contents: json,
+            is_empty: self.matches.is_empty(),
+        }])
+    }
+}
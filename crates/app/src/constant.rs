@@ -0,0 +1,380 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use common::data::constant::{AbilityConstant, ConstantResponse, FacetConstant, HeroFacets, IconUrls, SpiltConstant};
+use serde::{de::DeserializeOwned, Serialize};
+use snafu::ResultExt;
+use tracing::warn;
+
+use crate::{
+    error::{JsonSnafu, ReadFileSnafu},
+    persist::{PendingWrite, Store},
+};
+
+/// Result of resolving an id against the constants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup<'a> {
+    /// Id `0`, e.g. an empty item slot
+    Empty,
+    Known(&'a str),
+    /// Not in the constants, most likely added by a game patch after the last fetch
+    Unknown(i32),
+}
+
+impl Display for Lookup<'_> {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            Lookup::Empty => Ok(()),
+            Lookup::Known(name) => write!(f, "{}", name),
+            Lookup::Unknown(id) => write!(f, "#{}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ConstantKind {
+    Item,
+    Hero,
+    Ability,
+    /// Ids are the `hero_variant` of this hero
+    Facet {
+        hero_id: i32,
+    },
+    /// Download url of a hero, item or ability icon
+    Icon {
+        kind: &'static str,
+    },
+    /// Tier of an item in a neutral or enchantment slot
+    NeutralTier,
+}
+
+/// Every id to name map, as served by the local API
+#[derive(Serialize)]
+pub struct ConstantNames<'a> {
+    items: &'a HashMap<i32, String>,
+    heroes: &'a HashMap<i32, String>,
+    abilities: &'a HashMap<i32, AbilityConstant>,
+    facets: &'a HeroFacets,
+    neutral_tiers: &'a HashMap<i32, u8>,
+}
+
+#[derive(Default)]
+pub struct GameConstant {
+    items_map: HashMap<i32, String>,
+    heroes_map: HashMap<i32, String>,
+    abilities_map: HashMap<i32, AbilityConstant>,
+    facets_map: HeroFacets,
+    /// Tier of the neutral items and enchantments
+    neutral_tiers: HashMap<i32, u8>,
+    icon_urls: IconUrls,
+    is_loaded: bool,
+    /// Bumped on every update from Stratz
+    generation: u64,
+    /// Ids already reported as unknown, so each one is only logged once
+    unknown_ids: Mutex<HashSet<(ConstantKind, i32)>>,
+    /// An unknown id asks for one refresh of the constants per session
+    refresh_requested: AtomicBool,
+}
+
+impl Display for GameConstant {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "GameConstant {{ items: {}, heroes: {}, abilities: {}, facets: {}, is_loaded: {} }}",
+            self.items_map.len(),
+            self.heroes_map.len(),
+            self.abilities_map.len(),
+            self.facets_map.len(),
+            self.is_loaded
+        )
+    }
+}
+
+impl GameConstant {
+    pub fn from_config() -> Result<Self, crate::Error> {
+        let (items_map, heroes_map, abilities_map) = Self::read_json()?;
+        // The first icon without an url asks for a refresh
+        let icon_urls: IconUrls = read_optional("config/icons.json");
+        // Older configs have neither file, the first unknown facet or tier asks for a refresh
+        let facets_map: HeroFacets = read_optional("config/facets.json");
+        let neutral_tiers: HashMap<i32, u8> = read_optional("config/neutral_tiers.json");
+
+        Ok(Self {
+            items_map,
+            heroes_map,
+            abilities_map,
+            facets_map,
+            neutral_tiers,
+            icon_urls,
+            is_loaded: true,
+            ..Default::default()
+        })
+    }
+
+    /// Replace the constants with a fresh response from Stratz
+    pub fn update(
+        &mut self,
+        response: ConstantResponse,
+    ) {
+        self.icon_urls = response.icon_urls();
+        self.facets_map = response.facets();
+        self.neutral_tiers = response.neutral_tiers();
+        let (items, heroes, abilities) = response.spilt();
+        self.items_map = items;
+        self.heroes_map = heroes;
+        self.abilities_map = abilities;
+        self.is_loaded = true;
+        self.generation += 1;
+        self.unknown_ids.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.is_loaded
+    }
+
+    pub fn item(
+        &self,
+        id: i32,
+    ) -> Lookup<'_> {
+        self.lookup(ConstantKind::Item, id, self.items_map.get(&id).map(String::as_str))
+    }
+
+    pub fn hero(
+        &self,
+        id: i32,
+    ) -> Lookup<'_> {
+        self.lookup(ConstantKind::Hero, id, self.heroes_map.get(&id).map(String::as_str))
+    }
+
+    pub fn ability(
+        &self,
+        id: i32,
+    ) -> Lookup<'_> {
+        self.lookup(
+            ConstantKind::Ability,
+            id,
+            self.abilities_map.get(&id).map(|ability| ability.name.as_str()),
+        )
+    }
+
+    /// Tier of the item in a neutral or enchantment slot, one without a tier asks for a refresh
+    pub fn neutral_tier(
+        &self,
+        id: i32,
+    ) -> Option<u8> {
+        let tier = self.neutral_tiers.get(&id).copied();
+        if tier.is_none() {
+            self.lookup(ConstantKind::NeutralTier, id, None);
+        }
+        tier
+    }
+
+    /// Name of the facet picked as `variant` on `hero_id`
+    pub fn facet_name(
+        &self,
+        hero_id: i32,
+        variant: i32,
+    ) -> Lookup<'_> {
+        let facet = self.facet(hero_id, variant);
+        self.lookup(ConstantKind::Facet { hero_id }, variant, facet.map(|facet| facet.name.as_str()))
+    }
+
+    pub fn facet(
+        &self,
+        hero_id: i32,
+        variant: i32,
+    ) -> Option<&FacetConstant> {
+        self.facets_map.get(&hero_id)?.get(&variant)
+    }
+
+    pub fn is_talent(
+        &self,
+        id: i32,
+    ) -> bool {
+        self.abilities_map.get(&id).is_some_and(|ability| ability.is_talent)
+    }
+
+    /// Every known hero, sorted by name
+    pub fn heroes(&self) -> Vec<(i32, &str)> {
+        let mut heroes = self.heroes_map.iter().map(|(id, name)| (*id, name.as_str())).collect::<Vec<_>>();
+        heroes.sort_by(|a, b| a.1.cmp(b.1));
+        heroes
+    }
+
+    pub fn names(&self) -> ConstantNames<'_> {
+        ConstantNames {
+            items: &self.items_map,
+            heroes: &self.heroes_map,
+            abilities: &self.abilities_map,
+            facets: &self.facets_map,
+            neutral_tiers: &self.neutral_tiers,
+        }
+    }
+
+    pub fn icon_urls(&self) -> &IconUrls {
+        &self.icon_urls
+    }
+
+    /// Counts like an unknown id, so a cache without icon urls gets refreshed
+    pub fn missing_icon_url(
+        &self,
+        kind: &'static str,
+        id: i32,
+    ) {
+        self.lookup(ConstantKind::Icon { kind }, id, None);
+    }
+
+    /// `true` once, the first time an unknown id shows up after the constants are loaded
+    pub fn take_refresh_request(&self) -> bool {
+        let has_unknown = !self.unknown_ids.lock().unwrap_or_else(|e| e.into_inner()).is_empty();
+        has_unknown && !self.refresh_requested.swap(true, Ordering::Relaxed)
+    }
+
+    fn lookup<'a>(
+        &self,
+        kind: ConstantKind,
+        id: i32,
+        name: Option<&'a str>,
+    ) -> Lookup<'a> {
+        match (id, name) {
+            (0, _) => Lookup::Empty,
+            (_, Some(name)) => Lookup::Known(name),
+            (_, None) => {
+                // Nothing to refresh against before the first load
+                if self.is_loaded && self.unknown_ids.lock().unwrap_or_else(|e| e.into_inner()).insert((kind, id)) {
+                    warn!("Unknown {:?} id: {}", kind, id);
+                }
+                Lookup::Unknown(id)
+            }
+        }
+    }
+
+    fn read_json() -> Result<SpiltConstant, crate::Error> {
+        let items_json = std::fs::read_to_string("config/items.json").context(ReadFileSnafu { filename: "items.json" })?;
+        let heroes_json = std::fs::read_to_string("config/heroes.json").context(ReadFileSnafu { filename: "heroes.json" })?;
+
+        let items: HashMap<i32, String> = serde_json::from_str(&items_json).context(JsonSnafu)?;
+        let heroes: HashMap<i32, String> = serde_json::from_str(&heroes_json).context(JsonSnafu)?;
+        // Caches from before abilities were fetched have no such file, the first unknown ability
+        // asks for a refresh
+        let abilities: HashMap<i32, AbilityConstant> = read_optional("config/abilities.json");
+
+        Ok((items, heroes, abilities))
+    }
+}
+
+impl Store for GameConstant {
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn files(&self) -> Result<Vec<PendingWrite>, crate::Error> {
+        Ok(vec![
+            PendingWrite::pretty("config/items.json", &self.items_map, self.items_map.is_empty())?,
+            PendingWrite::pretty("config/heroes.json", &self.heroes_map, self.heroes_map.is_empty())?,
+            PendingWrite::pretty("config/abilities.json", &self.abilities_map, self.abilities_map.is_empty())?,
+            PendingWrite::pretty("config/facets.json", &self.facets_map, self.facets_map.is_empty())?,
+            PendingWrite::pretty("config/neutral_tiers.json", &self.neutral_tiers, self.neutral_tiers.is_empty())?,
+            PendingWrite::pretty("config/icons.json", &self.icon_urls, self.icon_urls.heroes.is_empty())?,
+        ])
+    }
+}
+
+/// A cache file added after the first release, empty when it is missing or unreadable so the
+/// rest of the constants still load
+fn read_optional<T: DeserializeOwned + Default>(filename: &str) -> T {
+    let Ok(json) = std::fs::read_to_string(filename) else {
+        return T::default();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        warn!("Ignoring malformed {}: {}", filename, e);
+        T::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let mut constant = GameConstant::default();
+        constant.items_map.insert(1, String::from("Blink Dagger"));
+        constant.is_loaded = true;
+
+        assert_eq!(constant.item(0), Lookup::Empty);
+        assert_eq!(constant.item(1), Lookup::Known("Blink Dagger"));
+        assert_eq!(constant.item(1234), Lookup::Unknown(1234));
+        assert_eq!(constant.item(1234).to_string(), "#1234");
+        assert!(constant.take_refresh_request());
+        assert!(!constant.take_refresh_request());
+    }
+
+    #[test]
+    fn missing_icon_url() {
+        let constant = GameConstant {
+            is_loaded: true,
+            ..Default::default()
+        };
+        constant.missing_icon_url("hero", 0);
+        assert!(!constant.take_refresh_request());
+        constant.missing_icon_url("hero", 1);
+        assert!(constant.take_refresh_request());
+    }
+
+    #[test]
+    fn missing_neutral_tier() {
+        let mut constant = GameConstant::default();
+        constant.neutral_tiers.insert(1, 3);
+        constant.is_loaded = true;
+
+        assert_eq!(constant.neutral_tier(0), None);
+        assert_eq!(constant.neutral_tier(1), Some(3));
+        assert!(!constant.take_refresh_request());
+        assert_eq!(constant.neutral_tier(2), None);
+        assert!(constant.take_refresh_request());
+    }
+
+    #[test]
+    fn missing_optional_file() {
+        let abilities: HashMap<i32, AbilityConstant> = read_optional("config/no_such_file.json");
+        assert!(abilities.is_empty());
+    }
+
+    #[test]
+    fn malformed_optional_file() {
+        let path = std::env::temp_dir().join("watchingir_malformed_neutral_tiers.json");
+        std::fs::write(&path, "{\"1\": \"T3\"}").unwrap();
+        let neutral_tiers: HashMap<i32, u8> = read_optional(path.to_str().unwrap());
+        assert!(neutral_tiers.is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn facet_lookup() {
+        let mut constant = GameConstant::default();
+        let facet = FacetConstant {
+            name: String::from("Magic Nullifier"),
+            description: String::new(),
+        };
+        constant.facets_map.entry(1).or_default().insert(1, facet);
+        constant.is_loaded = true;
+
+        assert_eq!(constant.facet_name(1, 1), Lookup::Known("Magic Nullifier"));
+        assert_eq!(constant.facet_name(1, 2).to_string(), "#2");
+        assert_eq!(constant.facet_name(2, 1), Lookup::Unknown(1));
+        assert!(constant.take_refresh_request());
+    }
+}
@@ -0,0 +1,116 @@
+//! `watchingir://match/<id>` links and the command line arguments that open a match
+
+pub const URL_SCHEME: &str = "watchingir";
+
+/// Command line flag that registers the url scheme and exits
+pub const REGISTER_FLAG: &str = "--register-url-scheme";
+
+/// Match id of `watchingir://match/<id>`, a trailing slash is allowed
+pub fn parse_match_url(url: &str) -> Option<i64> {
+    let path = url.strip_prefix(URL_SCHEME)?.strip_prefix("://")?;
+    let id = path.strip_prefix("match/")?.trim_end_matches('/');
+    id.parse().ok()
+}
+
+/// Match to open on start, from `--match <id>` or a `watchingir://` url passed by the OS
+pub fn match_from_args(args: impl IntoIterator<Item = String>) -> Option<i64> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--match" {
+            return args.next()?.parse().ok();
+        }
+        if let Some(match_id) = parse_match_url(&arg) {
+            return Some(match_id);
+        }
+    }
+    None
+}
+
+/// Register the current executable as the `watchingir://` handler of the current user
+#[cfg(target_os = "windows")]
+pub fn register_url_scheme() -> Result<(), crate::Error> {
+    use snafu::ResultExt;
+    use tracing::info;
+
+    use crate::error::CommandSnafu;
+
+    let exe = std::env::current_exe().context(CommandSnafu { command: "current_exe" })?;
+    let key = format!(r"HKCU\Software\Classes\{}", URL_SCHEME);
+    let command = format!("\"{}\" \"%1\"", exe.display());
+    run("reg", &["add", &key, "/ve", "/d", "URL:Watchingir", "/f"])?;
+    run("reg", &["add", &key, "/v", "URL Protocol", "/d", "", "/f"])?;
+    run("reg", &["add", &format!(r"{}\shell\open\command", key), "/ve", "/d", &command, "/f"])?;
+    info!("Registered {}:// to {}", URL_SCHEME, exe.display());
+    Ok(())
+}
+
+/// Register the current executable as the `watchingir://` handler of the current user
+#[cfg(target_os = "linux")]
+pub fn register_url_scheme() -> Result<(), crate::Error> {
+    use snafu::ResultExt;
+    use tracing::info;
+
+    use crate::error::{CommandSnafu, WriteFileSnafu};
+
+    let exe = std::env::current_exe().context(CommandSnafu { command: "current_exe" })?;
+    let home = std::env::var("HOME").unwrap_or_default();
+    let dir = format!("{}/.local/share/applications", home);
+    let path = format!("{}/watchingir.desktop", dir);
+    let desktop = format!(
+        "[Desktop Entry]\nType=Application\nName=Watchingir\nExec=\"{}\" %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        exe.display(),
+        URL_SCHEME
+    );
+    std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&path, desktop))
+        .context(WriteFileSnafu { filename: &path })?;
+    run(
+        "xdg-mime",
+        &["default", "watchingir.desktop", &format!("x-scheme-handler/{}", URL_SCHEME)],
+    )?;
+    info!("Registered {}:// to {}", URL_SCHEME, exe.display());
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn register_url_scheme() -> Result<(), crate::Error> {
+    use tracing::warn;
+
+    warn!("Registering {}:// is not supported on this platform", URL_SCHEME);
+    Ok(())
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn run(
+    program: &str,
+    args: &[&str],
+) -> Result<(), crate::Error> {
+    use snafu::{ensure, ResultExt};
+
+    use crate::error::{CommandSnafu, CommandStatusSnafu};
+
+    let status = std::process::Command::new(program)
+        .args(args)
+        .status()
+        .context(CommandSnafu { command: program })?;
+    ensure!(status.success(), CommandStatusSnafu { command: program, status });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_from_url_and_args() {
+        assert_eq!(parse_match_url("watchingir://match/7891234567"), Some(7891234567));
+        assert_eq!(parse_match_url("watchingir://match/7891234567/"), Some(7891234567));
+        assert_eq!(parse_match_url("watchingir://player/1"), None);
+        assert_eq!(parse_match_url("https://www.opendota.com/matches/1"), None);
+
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(match_from_args(args(&["--match", "42"])), Some(42));
+        assert_eq!(match_from_args(args(&["watchingir://match/42"])), Some(42));
+        assert_eq!(match_from_args(args(&[])), None);
+    }
+}
@@ -1,29 +1,17 @@
 use ir_aquila::stack_error;
 use snafu::{Location, Snafu};
 
-/// Top level error that wraps all other errors
+/// Top level error wrapping the errors of the other crates
 #[derive(Snafu)]
 #[snafu(visibility(pub(crate)))]
 #[stack_error]
 pub(crate) enum AppError {
-    #[snafu(display("CommonError"))]
-    Common {
-        #[snafu(implicit)]
-        location: Location,
-        source: common::Error,
-    },
     #[snafu(display("ServerError"))]
     Server {
         #[snafu(implicit)]
         location: Location,
         source: server::Error,
     },
-    #[snafu(display("RuntimeError"))]
-    Application {
-        #[snafu(implicit)]
-        location: Location,
-        source: Error,
-    },
 }
 
 #[derive(Snafu)]
@@ -53,6 +41,29 @@ pub(crate) enum Error {
         #[snafu(source)]
         error: serde_json::Error,
     },
+    #[snafu(display("Command: Error when running: {}", command))]
+    Command {
+        command: String,
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: std::io::Error,
+    },
+    #[snafu(display("CommandStatus: {} exited with {}", command, status))]
+    CommandStatus {
+        command: String,
+        status: std::process::ExitStatus,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Serve: Error when serving the local API on {}", address))]
+    Serve {
+        address: String,
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: std::io::Error,
+    },
     #[snafu(display("Toml: Error when de/serializing toml"))]
     Toml {
         #[snafu(implicit)]
@@ -5,8 +5,15 @@ use tracing_subscriber::{
     Layer,
 };
 
+mod api;
+mod archive;
+mod constant;
+mod deep_link;
 mod error;
 mod message;
+mod notes;
+mod overlay;
+mod persist;
 mod state;
 mod ui;
 
@@ -14,7 +21,20 @@ pub(crate) use error::Error;
 
 fn main() {
     init_log();
-    ui::launch().expect("Failed to start the app");
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == deep_link::REGISTER_FLAG) {
+        if let Err(e) = deep_link::register_url_scheme() {
+            tracing::error!("Failed to register url scheme: {}", e);
+        }
+        return;
+    }
+    if let Some(port) = api::port_from_args(&args) {
+        if let Err(e) = api::serve_headless(port) {
+            tracing::error!("Failed to serve the local API: {}", e);
+        }
+        return;
+    }
+    ui::launch(deep_link::match_from_args(args)).expect("Failed to start the app");
 }
 
 fn init_log() {
@@ -1,3 +1,15 @@
+use crate::ui::icon::IconKey;
+
 pub enum Task {
     UpdateMatchDetail,
+    /// Fetch the page of history right before the oldest archived match
+    LoadMoreMatches,
+    DownloadIcon(IconKey),
+    /// Select a match, fetching it first when it is not archived
+    OpenMatch(i64),
+    /// Show the player at `player_index` of an archived match in the comparison view
+    CompareMatch {
+        match_id: i64,
+        player_index: usize,
+    },
 }
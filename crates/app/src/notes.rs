@@ -0,0 +1,136 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::{
+    error::{JsonSnafu, ReadFileSnafu},
+    persist::{PendingWrite, Store},
+};
+
+/// Offered in the tag editor, any other tag can be typed in
+pub const PRESET_TAGS: [&str; 4] = ["scrim", "smurf", "throw", "review-later"];
+/// Highest star rating
+pub const MAX_RATING: u8 = 5;
+
+/// The user's own annotations on a match
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchNote {
+    pub text: String,
+    pub tags: Vec<String>,
+    /// `0` for unrated, up to `MAX_RATING` stars
+    pub rating: u8,
+}
+
+impl MatchNote {
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.tags.is_empty() && self.rating == 0
+    }
+
+    /// `needle` lowercased, matched against the text and the tags
+    pub fn contains(
+        &self,
+        needle: &str,
+    ) -> bool {
+        self.text.to_lowercase().contains(needle) || self.tags.iter().any(|tag| tag.to_lowercase().contains(needle))
+    }
+
+    /// `★★★☆☆`, empty when unrated
+    pub fn stars(&self) -> String {
+        if self.rating == 0 {
+            return String::new();
+        }
+        (1..=MAX_RATING).map(|star| if star <= self.rating { '★' } else { '☆' }).collect()
+    }
+
+    /// `★★★☆☆ scrim, throw`, for table cells and exports
+    pub fn summary(&self) -> String {
+        [self.stars(), self.tags.join(", ")]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Notes keyed by `match_id`, kept in `config/notes.json`
+#[derive(Default)]
+pub struct NoteBook {
+    notes: BTreeMap<i64, MatchNote>,
+    /// Bumped on every edit, an empty notebook is only written once the user removed notes
+    generation: u64,
+}
+
+impl NoteBook {
+    pub fn from_config() -> Result<Self, crate::Error> {
+        let json = std::fs::read_to_string("config/notes.json").context(ReadFileSnafu {
+            filename: "config/notes.json",
+        })?;
+        let notes = serde_json::from_str(&json).context(JsonSnafu)?;
+        Ok(Self { notes, generation: 0 })
+    }
+
+    pub fn get(
+        &self,
+        match_id: i64,
+    ) -> Option<&MatchNote> {
+        self.notes.get(&match_id)
+    }
+
+    /// Replace the note of `match_id`, an empty note removes it
+    pub fn set(
+        &mut self,
+        match_id: i64,
+        note: MatchNote,
+    ) {
+        if note.is_empty() {
+            self.notes.remove(&match_id);
+        } else {
+            self.notes.insert(match_id, note);
+        }
+        self.generation += 1;
+    }
+
+    /// Every tag in use plus the presets, sorted
+    pub fn tags(&self) -> BTreeSet<String> {
+        self.notes
+            .values()
+            .flat_map(|note| note.tags.iter().cloned())
+            .chain(PRESET_TAGS.iter().map(|tag| tag.to_string()))
+            .collect()
+    }
+}
+
+impl Store for NoteBook {
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn files(&self) -> Result<Vec<PendingWrite>, crate::Error> {
+        let is_empty = self.notes.is_empty() && self.generation == 0;
+        Ok(vec![PendingWrite::pretty("config/notes.json", &self.notes, is_empty)?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_note_removed() {
+        let mut notes = NoteBook::default();
+        let note = MatchNote {
+            text: String::from("Lost lane to the offlaner"),
+            tags: vec![String::from("review-later")],
+            rating: 2,
+        };
+        notes.set(1, note.clone());
+        assert_eq!(notes.get(1), Some(&note));
+        assert!(note.contains("review"));
+        assert_eq!(note.stars(), "★★☆☆☆");
+
+        notes.set(1, MatchNote::default());
+        assert_eq!(notes.get(1), None);
+    }
+}
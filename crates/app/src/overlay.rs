@@ -0,0 +1,149 @@
+//! Compact summary for stream viewers, shown in the overlay viewport and served at `/overlay`
+
+use chrono::NaiveDate;
+use common::{data::matches::MatchDetailView, html::escape_html, stats};
+use serde::Serialize;
+
+use crate::constant::GameConstant;
+
+/// Results shown as W/L pips when not configured
+pub const DEFAULT_OVERLAY_RESULTS: usize = 10;
+/// How often the `/overlay` page asks whether the summary changed
+const POLL_SECONDS: u32 = 10;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OverlaySummary {
+    /// `true` for a win, newest first
+    pub results: Vec<bool>,
+    pub today: stats::Record,
+    pub last_game: Option<LastGame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LastGame {
+    pub match_id: i64,
+    pub win: bool,
+    pub hero_id: i32,
+    pub hero: String,
+    pub kills: i32,
+    pub deaths: i32,
+    pub assists: i32,
+    pub gold_per_min: i32,
+}
+
+impl OverlaySummary {
+    /// `views` newest first, as returned by `MatchArchive::views`
+    pub fn new<'a>(
+        views: impl IntoIterator<Item = &'a MatchDetailView> + Clone,
+        constant: &GameConstant,
+        results: usize,
+        today: NaiveDate,
+    ) -> Self {
+        let last_game = views.clone().into_iter().next().map(|view| {
+            let player = view.player_detail();
+            LastGame {
+                match_id: view.match_id(),
+                win: view.win(),
+                hero_id: player.hero_id,
+                hero: constant.hero(player.hero_id).to_string(),
+                kills: player.kills,
+                deaths: player.deaths,
+                assists: player.assists,
+                gold_per_min: player.gold_per_min,
+            }
+        });
+        Self {
+            results: views.clone().into_iter().take(results).map(MatchDetailView::win).collect(),
+            today: stats::Record::of(stats::played_on(views, today)),
+            last_game,
+        }
+    }
+
+    /// Standalone page for an OBS browser source. It polls `/overlay.json` and reloads itself once
+    /// the summary changes, e.g. after a new match is fetched.
+    pub fn to_html(&self) -> String {
+        let pips = self
+            .results
+            .iter()
+            .map(|win| {
+                if *win {
+                    r#"<span class="pip win">W</span>"#
+                } else {
+                    r#"<span class="pip loss">L</span>"#
+                }
+            })
+            .collect::<String>();
+        let last_game = match &self.last_game {
+            Some(game) => format!(
+                r#"<div class="last {}">{} {}/{}/{} {} GPM</div>"#,
+                if game.win { "win" } else { "loss" },
+                escape_html(&game.hero),
+                game.kills,
+                game.deaths,
+                game.assists,
+                game.gold_per_min
+            ),
+            None => String::new(),
+        };
+        // `<` only shows up inside JSON strings, escaped so a hero name can't end the script early
+        let shown = serde_json::to_string(self).unwrap_or_default().replace('<', r"\u003c");
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Watchingir Overlay</title>
+<style>
+body {{ margin: 0; font: bold 20px sans-serif; color: #fff; background: transparent; text-shadow: 0 0 3px #000; }}
+.pip {{ display: inline-block; width: 22px; margin-right: 2px; text-align: center; border-radius: 3px; }}
+.pip.win, .last.win {{ background: rgba(92, 184, 92, 0.8); }}
+.pip.loss, .last.loss {{ background: rgba(217, 83, 79, 0.8); }}
+.last {{ display: inline-block; padding: 0 4px; border-radius: 3px; }}
+</style>
+</head>
+<body>
+<div>{pips}</div>
+<div>今日 {today}</div>
+{last_game}
+<script>
+const shown = {shown};
+setInterval(async () => {{
+  try {{
+    const latest = await (await fetch("/overlay.json" + location.search)).json();
+    if (JSON.stringify(latest) !== JSON.stringify(shown)) location.reload();
+  }} catch (e) {{}}
+}}, {poll});
+</script>
+</body>
+</html>
+"#,
+            today = self.today,
+            poll = POLL_SECONDS * 1000,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_hero_name() {
+        let summary = OverlaySummary {
+            last_game: Some(LastGame {
+                match_id: 1,
+                win: true,
+                hero_id: 1,
+                hero: String::from("<Anti-Mage>"),
+                kills: 10,
+                deaths: 2,
+                assists: 5,
+                gold_per_min: 700,
+            }),
+            ..Default::default()
+        };
+        let html = summary.to_html();
+        assert!(html.contains("&lt;Anti-Mage&gt; 10/2/5 700 GPM"));
+        assert!(!html.contains("<Anti-Mage>"));
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use egui::mutex::RwLock;
+use serde::Serialize;
+use snafu::ResultExt;
+use tracing::{debug, error, warn};
+
+use crate::{
+    archive::MatchArchive,
+    constant::GameConstant,
+    error::{JsonSnafu, WriteFileSnafu},
+    notes::NoteBook,
+    state::AppState,
+};
+
+/// How often eframe's `save` hook writes the changed caches to disk while the app is running
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A file serialized from one of the stores, ready for `write_atomic`
+pub struct PendingWrite {
+    pub path: &'static str,
+    pub contents: String,
+    pub is_empty: bool,
+}
+
+impl PendingWrite {
+    pub fn pretty(
+        path: &'static str,
+        value: &impl Serialize,
+        is_empty: bool,
+    ) -> Result<Self, crate::Error> {
+        let contents = serde_json::to_string_pretty(value).context(JsonSnafu)?;
+        Ok(Self { path, contents, is_empty })
+    }
+
+    pub fn write(&self) -> Result<(), crate::Error> {
+        write_atomic(self.path, &self.contents, self.is_empty)
+    }
+}
+
+/// Data kept under `config/`, written only after it changed
+pub trait Store {
+    /// Changes whenever the data does
+    fn generation(&self) -> u64;
+
+    fn files(&self) -> Result<Vec<PendingWrite>, crate::Error>;
+}
+
+/// What was last written to disk
+#[derive(Default)]
+struct Saved {
+    state: String,
+    constant: u64,
+    archive: u64,
+    notes: u64,
+}
+
+/// Saves the stores that changed since the last save on a background thread. The UI never waits
+/// for the disk, and the store locks are only held while serializing.
+pub struct Persistence {
+    constant: Arc<RwLock<GameConstant>>,
+    archive: Arc<RwLock<MatchArchive>>,
+    notes: Arc<RwLock<NoteBook>>,
+    saved: Arc<Mutex<Saved>>,
+    /// At most one save runs at a time
+    running: Option<JoinHandle<()>>,
+}
+
+impl Persistence {
+    /// Everything loaded so far counts as saved
+    pub fn new(
+        state: &AppState,
+        constant: Arc<RwLock<GameConstant>>,
+        archive: Arc<RwLock<MatchArchive>>,
+        notes: Arc<RwLock<NoteBook>>,
+    ) -> Self {
+        let saved = Saved {
+            state: state.file().map(|file| file.contents).unwrap_or_default(),
+            constant: constant.read().generation(),
+            archive: archive.read().generation(),
+            notes: notes.read().generation(),
+        };
+        Self {
+            constant,
+            archive,
+            notes,
+            saved: Arc::new(Mutex::new(saved)),
+            running: None,
+        }
+    }
+
+    /// Start a background save, skipped while the previous one is still running
+    pub fn save(
+        &mut self,
+        state: &AppState,
+    ) {
+        if self.running.as_ref().is_some_and(|running| !running.is_finished()) {
+            debug!("Previous save still running, skip this one");
+            return;
+        }
+        let job = self.job(state);
+        self.running = Some(std::thread::spawn(job));
+    }
+
+    /// Wait for a running save, then save what is left on this thread, e.g. on exit
+    pub fn flush(
+        &mut self,
+        state: &AppState,
+    ) {
+        if let Some(running) = self.running.take() {
+            if running.join().is_err() {
+                error!("Background save panicked");
+            }
+        }
+        self.job(state)();
+    }
+
+    fn job(
+        &self,
+        state: &AppState,
+    ) -> impl FnOnce() + Send + 'static {
+        // The state is small and owned by the UI, so it is serialized right away
+        let state = state.file();
+        let constant = Arc::clone(&self.constant);
+        let archive = Arc::clone(&self.archive);
+        let notes = Arc::clone(&self.notes);
+        let saved = Arc::clone(&self.saved);
+        move || {
+            let mut saved = saved.lock().unwrap_or_else(|e| e.into_inner());
+            match state {
+                Ok(state) if state.contents != saved.state => match state.write() {
+                    Ok(()) => saved.state = state.contents,
+                    Err(e) => error!("Save app state error: {}", e),
+                },
+                Ok(_) => {}
+                Err(e) => error!("Save app state error: {}", e),
+            }
+            save_changed(&constant, &mut saved.constant, "game constant");
+            save_changed(&archive, &mut saved.archive, "match archive");
+            save_changed(&notes, &mut saved.notes, "match notes");
+        }
+    }
+}
+
+/// Serialize under the read lock, write after releasing it
+fn save_changed(
+    store: &RwLock<impl Store>,
+    saved: &mut u64,
+    name: &str,
+) {
+    let (generation, files) = {
+        let store = store.read();
+        if store.generation() == *saved {
+            return;
+        }
+        (store.generation(), store.files())
+    };
+    match files.and_then(|files| files.iter().try_for_each(PendingWrite::write)) {
+        Ok(()) => *saved = generation,
+        Err(e) => error!("Save {} error: {}", name, e),
+    }
+}
+
+/// Write `contents` to a temp file next to `path` and rename it over `path`, so a crash mid-write
+/// never leaves a truncated file behind. An empty value never replaces an existing file, e.g. the
+/// default constants after a failed load must not wipe the cache.
+pub fn write_atomic(
+    path: &str,
+    contents: &str,
+    is_empty: bool,
+) -> Result<(), crate::Error> {
+    if is_empty && Path::new(path).exists() {
+        warn!("Refuse to overwrite {} with an empty value", path);
+        return Ok(());
+    }
+
+    let tmp = format!("{}.tmp", path);
+    let res = match Path::new(path).parent() {
+        Some(dir) => std::fs::create_dir_all(dir),
+        None => Ok(()),
+    }
+    .and_then(|_| std::fs::write(&tmp, contents))
+    .and_then(|_| std::fs::rename(&tmp, path));
+    res.context(WriteFileSnafu { filename: path })?;
+    debug!("Saved {}", path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_populated_file() {
+        let dir = std::env::temp_dir().join(format!("watchingir-persist-{}", std::process::id()));
+        let path = dir.join("items.json");
+        let path = path.to_str().unwrap();
+
+        write_atomic(path, r#"{"1":"Blink Dagger"}"#, false).unwrap();
+        write_atomic(path, "{}", true).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), r#"{"1":"Blink Dagger"}"#);
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    struct Counter {
+        generation: u64,
+        path: &'static str,
+    }
+
+    impl Store for Counter {
+        fn generation(&self) -> u64 {
+            self.generation
+        }
+
+        fn files(&self) -> Result<Vec<PendingWrite>, crate::Error> {
+            Ok(vec![PendingWrite {
+                path: self.path,
+                contents: self.generation.to_string(),
+                is_empty: false,
+            }])
+        }
+    }
+
+    #[test]
+    fn only_changed_stores_written() {
+        let dir = std::env::temp_dir().join(format!("watchingir-store-{}", std::process::id()));
+        let path: &'static str = Box::leak(dir.join("counter.txt").to_str().unwrap().to_owned().into_boxed_str());
+        let store = RwLock::new(Counter { generation: 1, path });
+        let mut saved = 1;
+
+        save_changed(&store, &mut saved, "counter");
+        assert!(!Path::new(path).exists());
+
+        store.write().generation = 2;
+        save_changed(&store, &mut saved, "counter");
+        assert_eq!(saved, 2);
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "2");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
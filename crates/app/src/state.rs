@@ -1,15 +1,81 @@
+use common::{data::constant::ConstantLanguage, goal::Goal};
 use config::Config;
+use eframe::egui::ThemePreference;
 use serde::{Deserialize, Serialize};
+use server::courier::STEAM_API;
 use snafu::ResultExt;
-use tracing::{error, info};
 
-use crate::error::{TomlSnafu, WriteFileSnafu};
+use crate::{api::DEFAULT_PORT, error::TomlSnafu, overlay::DEFAULT_OVERLAY_RESULTS, persist::PendingWrite};
 
 #[derive(Deserialize, Serialize, Default, Debug)]
 pub struct AppState {
     pub steam_api_key: String,
     pub stratz_api_key: String,
     pub account_id: i64,
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub goals: Vec<Goal>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataSource {
+    /// The official Steam Web API
+    #[default]
+    Steam,
+    /// A Steam Web API mirror at `Settings::mirror_url`
+    Mirror,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: ThemePreference,
+    pub ui_scale: f32,
+    /// A font file on disk, `None` for the bundled LXGW WenKai
+    pub font_path: Option<String>,
+    /// in minutes, `0` turns auto-refresh off
+    pub refresh_interval: u32,
+    pub data_source: DataSource,
+    pub mirror_url: String,
+    pub language: ConstantLanguage,
+    /// Serve the local JSON API on `127.0.0.1:api_port`
+    pub api_enabled: bool,
+    pub api_port: u16,
+    /// W/L pips in the stream overlay
+    pub overlay_results: usize,
+    /// Suggest a break after `tilt_threshold` losses in a row with falling KDA and GPM
+    pub break_reminder: bool,
+    pub tilt_threshold: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: ThemePreference::System,
+            ui_scale: 1.0,
+            font_path: None,
+            refresh_interval: 0,
+            data_source: DataSource::Steam,
+            mirror_url: String::new(),
+            language: ConstantLanguage::default(),
+            api_enabled: false,
+            api_port: DEFAULT_PORT,
+            overlay_results: DEFAULT_OVERLAY_RESULTS,
+            break_reminder: true,
+            tilt_threshold: 3,
+        }
+    }
+}
+
+impl Settings {
+    /// Host the match requests go to
+    pub fn steam_api(&self) -> &str {
+        match self.data_source {
+            DataSource::Mirror if !self.mirror_url.is_empty() => &self.mirror_url,
+            _ => STEAM_API,
+        }
+    }
 }
 
 impl AppState {
@@ -21,29 +87,20 @@ impl AppState {
             return None;
         }
 
-        let res = state.unwrap().try_deserialize::<AppState>();
-        match res {
-            Ok(state) => Some(state),
-            Err(_) => None,
-        }
+        state.unwrap().try_deserialize::<AppState>().ok()
     }
 
-    fn save(&self) -> Result<(), crate::Error> {
-        let toml_string = toml::to_string(&self).context(TomlSnafu)?;
-        info!("Current AppState: \n{}", toml_string);
-        std::fs::write("config/save.toml", toml_string).context(WriteFileSnafu {
-            filename: "config/save.toml",
-        })?;
-        Ok(())
+    /// Nothing entered yet, e.g. the default after `config/save.toml` failed to load
+    pub fn is_empty(&self) -> bool {
+        self.steam_api_key.is_empty() && self.stratz_api_key.is_empty() && self.account_id == 0
     }
-}
 
-impl Drop for AppState {
-    fn drop(&mut self) {
-        info!("Drop AppState, save current state to config/save.toml");
-        if let Err(e) = self.save() {
-            error!("save config error: {}", e);
-        }
+    pub fn file(&self) -> Result<PendingWrite, crate::Error> {
+        Ok(PendingWrite {
+            path: "config/save.toml",
+            contents: toml::to_string(&self).context(TomlSnafu)?,
+            is_empty: self.is_empty(),
+        })
     }
 }
 
@@ -56,4 +113,32 @@ mod tests {
         let state = AppState::try_from_config();
         println!("{:?}", state);
     }
+
+    #[test]
+    fn settings_round_trip() {
+        let state = AppState {
+            settings: Settings {
+                theme: ThemePreference::Dark,
+                font_path: Some(String::from("C:/Windows/Fonts/msyh.ttc")),
+                data_source: DataSource::Mirror,
+                language: ConstantLanguage::English,
+                ..Default::default()
+            },
+            goals: vec![Goal {
+                name: String::from("少死"),
+                heroes: vec![1, 8],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let toml_string = toml::to_string(&state).unwrap();
+        let loaded = Config::builder()
+            .add_source(config::File::from_str(&toml_string, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize::<AppState>()
+            .unwrap();
+        assert_eq!(loaded.settings, state.settings);
+        assert_eq!(loaded.goals, state.goals);
+    }
 }
@@ -0,0 +1,69 @@
+use common::streak::{self, Tilt};
+use eframe::egui;
+use egui::{RichText, UserAttentionType, ViewportCommand};
+use tracing::info;
+
+use super::DIRE_COLOR;
+use crate::archive::MatchArchive;
+
+/// `tilt` is recomputed at least this often, in seconds, so the banner goes away with the session
+const RECHECK_INTERVAL: i64 = 10 * 60;
+
+/// Banner suggesting a break while the ongoing session looks like tilt
+#[derive(Default)]
+pub struct BreakReminder {
+    /// Archive generation, account, threshold and `RECHECK_INTERVAL` slot behind `tilt`
+    built_from: Option<(u64, i64, usize, i64)>,
+    tilt: Option<Tilt>,
+    /// Latest match of the last tilt the window asked for attention for
+    notified: Option<i64>,
+    /// Latest match of the last dismissed banner
+    dismissed: Option<i64>,
+}
+
+impl BreakReminder {
+    /// Call before the central panel, the banner is a top panel
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        archive: &MatchArchive,
+        account_id: i64,
+        threshold: usize,
+    ) {
+        let now = chrono::Utc::now().timestamp();
+        let key = (archive.generation(), account_id, threshold, now / RECHECK_INTERVAL);
+        if self.built_from != Some(key) {
+            self.tilt = streak::tilt(archive.views(account_id).iter(), threshold, now);
+            self.built_from = Some(key);
+        }
+
+        let Some(tilt) = self.tilt else {
+            return;
+        };
+        if self.dismissed == Some(tilt.match_id) {
+            return;
+        }
+        if self.notified != Some(tilt.match_id) {
+            info!("Tilt detected: {:?}", tilt);
+            ctx.send_viewport_cmd(ViewportCommand::RequestUserAttention(UserAttentionType::Informational));
+            self.notified = Some(tilt.match_id);
+        }
+
+        egui::TopBottomPanel::top("break_reminder").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(format!(
+                        "已连败 {} 场，KDA {:.1} → {:.1}，GPM {} → {}，休息一下吧",
+                        tilt.losses, tilt.kda.0, tilt.kda.1, tilt.gold_per_min.0, tilt.gold_per_min.1
+                    ))
+                    .strong()
+                    .color(DIRE_COLOR),
+                );
+                if ui.button("知道了").clicked() {
+                    self.dismissed = Some(tilt.match_id);
+                }
+            });
+        });
+        ctx.request_repaint_after(std::time::Duration::from_secs(RECHECK_INTERVAL as u64));
+    }
+}
@@ -0,0 +1,137 @@
+use common::data::building::{Barracks, BarracksKind, Lane, TeamBuildings, Tower};
+use eframe::egui;
+use egui::{Color32, Rect, Sense, Stroke, Vec2};
+
+use super::{DIRE_COLOR, RADIANT_COLOR};
+
+const MAP_SIZE: f32 = 220.0;
+const TOWER_SIZE: f32 = 9.0;
+const BARRACKS_SIZE: f32 = 6.0;
+
+const DESTROYED_COLOR: Color32 = Color32::from_gray(70);
+
+/// Minimap-style view of which towers and barracks are still standing at the end of the game
+pub fn building_map(
+    ui: &mut egui::Ui,
+    radiant: &TeamBuildings,
+    dire: &TeamBuildings,
+) {
+    ui.horizontal(|ui| {
+        let (response, painter) = ui.allocate_painter(Vec2::splat(MAP_SIZE), Sense::hover());
+        let map = response.rect;
+        painter.rect_filled(map, 4.0, Color32::from_rgb(30, 36, 30));
+        // river
+        painter.line_segment(
+            [map.lerp_inside(Vec2::new(0.0, 0.0)), map.lerp_inside(Vec2::new(1.0, 1.0))],
+            Stroke::new(6.0, Color32::from_rgb(40, 60, 90)),
+        );
+
+        let mut hovered = None;
+        for (is_radiant, buildings) in [(true, radiant), (false, dire)] {
+            let side = if is_radiant { "Radiant" } else { "Dire" };
+            let color = if is_radiant { RADIANT_COLOR } else { DIRE_COLOR };
+
+            // ancient
+            painter.circle_filled(map.lerp_inside(position(is_radiant, Vec2::new(0.12, 0.88))), 7.0, color);
+
+            for (tower, standing) in buildings.towers.towers() {
+                let rect = Rect::from_center_size(map.lerp_inside(tower_position(is_radiant, tower)), Vec2::splat(TOWER_SIZE));
+                painter.rect_filled(rect, 1.0, if standing { color } else { DESTROYED_COLOR });
+                if response.hover_pos().is_some_and(|pos| rect.contains(pos)) {
+                    hovered = Some(format!("{} {} ({})", side, tower.name(), status_text(standing)));
+                }
+            }
+            for (barracks, standing) in buildings.barracks.barracks() {
+                let rect = Rect::from_center_size(map.lerp_inside(barracks_position(is_radiant, barracks)), Vec2::splat(BARRACKS_SIZE));
+                painter.rect_filled(rect, 0.0, if standing { color } else { DESTROYED_COLOR });
+                if response.hover_pos().is_some_and(|pos| rect.contains(pos)) {
+                    hovered = Some(format!("{} {} ({})", side, barracks.name(), status_text(standing)));
+                }
+            }
+        }
+        if let Some(text) = hovered {
+            response.on_hover_text_at_pointer(text);
+        }
+
+        ui.vertical(|ui| {
+            for (name, buildings) in [("天辉", radiant), ("夜魇", dire)] {
+                ui.label(format!(
+                    "{}: 防御塔 {}/{}, 兵营 {}/{}",
+                    name,
+                    buildings.towers.standing(),
+                    Tower::ALL.len(),
+                    buildings.barracks.standing(),
+                    Barracks::ALL.len()
+                ));
+            }
+        });
+    });
+}
+
+fn status_text(standing: bool) -> &'static str {
+    if standing {
+        "standing"
+    } else {
+        "destroyed"
+    }
+}
+
+/// Radiant positions are laid out in the bottom left corner, dire mirrors them through the center
+/// of the map with top and bot lanes swapped
+fn position(
+    is_radiant: bool,
+    radiant_position: Vec2,
+) -> Vec2 {
+    if is_radiant {
+        radiant_position
+    } else {
+        Vec2::new(1.0, 1.0) - radiant_position
+    }
+}
+
+fn mirrored_lane(
+    is_radiant: bool,
+    lane: Lane,
+) -> Lane {
+    match (is_radiant, lane) {
+        (false, Lane::Top) => Lane::Bot,
+        (false, Lane::Bot) => Lane::Top,
+        (_, lane) => lane,
+    }
+}
+
+fn tower_position(
+    is_radiant: bool,
+    tower: Tower,
+) -> Vec2 {
+    let radiant_position = match (mirrored_lane(is_radiant, tower.lane), tower.tier) {
+        (Lane::Top, 1) => Vec2::new(0.1, 0.38),
+        (Lane::Top, 2) => Vec2::new(0.1, 0.56),
+        (Lane::Top, 3) => Vec2::new(0.08, 0.7),
+        (Lane::Mid, 1) => Vec2::new(0.42, 0.58),
+        (Lane::Mid, 2) => Vec2::new(0.33, 0.67),
+        (Lane::Mid, 3) => Vec2::new(0.24, 0.76),
+        (Lane::Bot, 1) => Vec2::new(0.8, 0.9),
+        (Lane::Bot, 2) => Vec2::new(0.5, 0.9),
+        (Lane::Bot, 3) => Vec2::new(0.3, 0.92),
+        (Lane::Top, _) => Vec2::new(0.12, 0.8),
+        (Lane::Bot, _) | (Lane::Mid, _) => Vec2::new(0.2, 0.88),
+    };
+    position(is_radiant, radiant_position)
+}
+
+fn barracks_position(
+    is_radiant: bool,
+    barracks: Barracks,
+) -> Vec2 {
+    let offset = match barracks.kind {
+        BarracksKind::Melee => Vec2::new(-0.025, 0.0),
+        BarracksKind::Ranged => Vec2::new(0.025, 0.0),
+    };
+    let radiant_position = match mirrored_lane(is_radiant, barracks.lane) {
+        Lane::Top => Vec2::new(0.08, 0.75),
+        Lane::Mid => Vec2::new(0.2, 0.8),
+        Lane::Bot => Vec2::new(0.25, 0.95),
+    };
+    position(is_radiant, radiant_position + offset)
+}
@@ -0,0 +1,251 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use common::{
+    compare::{compare, StatDelta},
+    data::matches::{MatchDetailView, PlayerDetail},
+};
+use eframe::egui;
+use egui::mutex::RwLock;
+
+use super::{Component, DIRE_COLOR, RADIANT_COLOR};
+use crate::{
+    archive::{MatchArchive, Views},
+    constant::GameConstant,
+    state::AppState,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareMode {
+    /// Two players of one match, e.g. lane opponents
+    SameMatch,
+    /// The tracked account on the same hero in two of its games
+    SameHero,
+}
+
+pub struct ComparePanel {
+    archive: Arc<RwLock<MatchArchive>>,
+    mode: CompareMode,
+    match_id: Option<i64>,
+    /// Indices into the players of `match_id`
+    players: (usize, usize),
+    hero_id: Option<i32>,
+    /// Two games of the tracked account on `hero_id`
+    matches: (Option<i64>, Option<i64>),
+}
+
+impl ComparePanel {
+    pub fn new(archive: Arc<RwLock<MatchArchive>>) -> Self {
+        Self {
+            archive,
+            mode: CompareMode::SameMatch,
+            match_id: None,
+            players: (0, 5),
+            hero_id: None,
+            matches: (None, None),
+        }
+    }
+
+    /// Compare `players[player_index]` of `match_id` against the enemy in the same slot, the real
+    /// lane opponent can be picked from the list afterwards
+    pub fn select_match(
+        &mut self,
+        match_id: i64,
+        player_index: usize,
+    ) {
+        self.mode = CompareMode::SameMatch;
+        self.match_id = Some(match_id);
+        self.players = (player_index, (player_index + 5) % 10);
+    }
+
+    fn same_match_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        views: &Views,
+        constant: &GameConstant,
+    ) {
+        ui.horizontal(|ui| {
+            match_combo(ui, "compare_match", &mut self.match_id, views.iter(), constant);
+        });
+        let archive = self.archive.read();
+        let Some(detail) = self.match_id.and_then(|id| archive.get(id)) else {
+            ui.label("选择一场比赛");
+            return;
+        };
+        let players = detail.players();
+        ui.horizontal(|ui| {
+            player_combo(ui, "compare_left_player", &mut self.players.0, players, constant);
+            ui.label("vs");
+            player_combo(ui, "compare_right_player", &mut self.players.1, players, constant);
+        });
+        ui.separator();
+        if let (Some(left), Some(right)) = (players.get(self.players.0), players.get(self.players.1)) {
+            comparison(ui, left, right, constant);
+        }
+    }
+
+    fn same_hero_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        views: &Views,
+        constant: &GameConstant,
+    ) {
+        let mut heroes = views.iter().map(|view| view.player_detail().hero_id).collect::<Vec<_>>();
+        heroes.sort_by_key(|id| constant.hero(*id).to_string());
+        heroes.dedup();
+
+        let hero_text = self.hero_id.map(|id| constant.hero(id).to_string()).unwrap_or(String::from("Hero"));
+        let previous = self.hero_id;
+        egui::ComboBox::from_id_salt("compare_hero").selected_text(hero_text).show_ui(ui, |ui| {
+            for id in heroes {
+                ui.selectable_value(&mut self.hero_id, Some(id), constant.hero(id).to_string());
+            }
+        });
+        if self.hero_id != previous {
+            self.matches = (None, None);
+        }
+        let Some(hero_id) = self.hero_id else {
+            ui.label("选择一个英雄");
+            return;
+        };
+
+        let games = views.iter().filter(|view| view.player_detail().hero_id == hero_id);
+        ui.horizontal(|ui| {
+            match_combo(ui, "compare_left_match", &mut self.matches.0, games.clone(), constant);
+            ui.label("vs");
+            match_combo(ui, "compare_right_match", &mut self.matches.1, games, constant);
+        });
+        ui.separator();
+
+        let find = |match_id: Option<i64>| views.iter().find(|view| Some(view.match_id()) == match_id);
+        if let (Some(left), Some(right)) = (find(self.matches.0), find(self.matches.1)) {
+            comparison(ui, left.player_detail(), right.player_detail(), constant);
+        }
+    }
+}
+
+impl Component for ComparePanel {
+    fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut AppState,
+        constant: &Arc<RwLock<GameConstant>>,
+    ) {
+        // Newest first, shared with the other panels
+        let views = self.archive.read().views(state.account_id);
+        let constant = constant.read();
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("对比");
+                ui.selectable_value(&mut self.mode, CompareMode::SameMatch, "同场对比");
+                ui.selectable_value(&mut self.mode, CompareMode::SameHero, "同英雄对比");
+            });
+            ui.separator();
+            egui::ScrollArea::vertical().show(ui, |ui| match self.mode {
+                CompareMode::SameMatch => self.same_match_ui(ui, &views, &constant),
+                CompareMode::SameHero => self.same_hero_ui(ui, &views, &constant),
+            });
+        });
+    }
+}
+
+fn match_combo<'a>(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    match_id: &mut Option<i64>,
+    views: impl Iterator<Item = &'a MatchDetailView>,
+    constant: &GameConstant,
+) {
+    let label = |view: &MatchDetailView| {
+        format!(
+            "{} {} {}",
+            view.start_time_col(),
+            constant.hero(view.player_detail().hero_id),
+            view.win_col()
+        )
+    };
+    let views = views.collect::<Vec<_>>();
+    let selected = views
+        .iter()
+        .find(|view| Some(view.match_id()) == *match_id)
+        .map(|view| label(view))
+        .or(match_id.map(|id| id.to_string()))
+        .unwrap_or(String::from("Match"));
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(selected)
+        .width(260.0)
+        .show_ui(ui, |ui| {
+            for view in views {
+                ui.selectable_value(match_id, Some(view.match_id()), label(view));
+            }
+        });
+}
+
+fn player_combo(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    index: &mut usize,
+    players: &[PlayerDetail],
+    constant: &GameConstant,
+) {
+    let name = |player: &PlayerDetail| {
+        let team = if player.is_radiant() { "天辉" } else { "夜魇" };
+        format!("{} {}", team, constant.hero(player.hero_id))
+    };
+    let selected = players.get(*index).map(name).unwrap_or_default();
+    egui::ComboBox::from_id_salt(id_salt).selected_text(selected).show_ui(ui, |ui| {
+        for (i, player) in players.iter().enumerate() {
+            let color = if player.is_radiant() { RADIANT_COLOR } else { DIRE_COLOR };
+            ui.selectable_value(index, i, egui::RichText::new(name(player)).color(color));
+        }
+    });
+}
+
+/// Stat table with the better value of each row in green, then both inventories
+fn comparison(
+    ui: &mut egui::Ui,
+    left: &PlayerDetail,
+    right: &PlayerDetail,
+    constant: &GameConstant,
+) {
+    let deltas = compare(left, right);
+    egui::Grid::new("compare_stats").num_columns(4).striped(true).show(ui, |ui| {
+        ui.label("");
+        ui.strong(constant.hero(left.hero_id).to_string());
+        ui.strong(constant.hero(right.hero_id).to_string());
+        ui.strong("Δ");
+        ui.end_row();
+        for delta in &deltas {
+            delta_row(ui, delta);
+        }
+    });
+
+    ui.add_space(10.0);
+    ui.heading("装备");
+    egui::Grid::new("compare_items").num_columns(2).spacing([40.0, 4.0]).show(ui, |ui| {
+        for (left_item, right_item) in left.items().into_iter().zip(right.items()) {
+            ui.label(constant.item(left_item).to_string());
+            ui.label(constant.item(right_item).to_string());
+            ui.end_row();
+        }
+    });
+}
+
+fn delta_row(
+    ui: &mut egui::Ui,
+    delta: &StatDelta,
+) {
+    let (left_color, right_color) = match delta.left_is() {
+        Ordering::Greater => (Some(RADIANT_COLOR), Some(DIRE_COLOR)),
+        Ordering::Less => (Some(DIRE_COLOR), Some(RADIANT_COLOR)),
+        Ordering::Equal => (None, None),
+    };
+    let colored = |ui: &mut egui::Ui, text: String, color: Option<egui::Color32>| match color {
+        Some(color) => ui.colored_label(color, text),
+        None => ui.label(text),
+    };
+    ui.label(delta.stat.label());
+    colored(ui, delta.left.to_string(), left_color);
+    colored(ui, delta.right.to_string(), right_color);
+    colored(ui, format!("{:+}", delta.delta()), left_color);
+    ui.end_row();
+}
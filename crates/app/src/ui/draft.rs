@@ -0,0 +1,59 @@
+use common::data::matches::PickBan;
+use eframe::egui;
+use egui::RichText;
+
+use super::{DIRE_COLOR, RADIANT_COLOR};
+use crate::constant::GameConstant;
+
+/// Picks and bans in draft order, one column per step with the radiant on the upper row and the
+/// dire on the lower row
+pub fn draft_timeline(
+    ui: &mut egui::Ui,
+    picks_bans: &[PickBan],
+    constant: &GameConstant,
+) {
+    egui::ScrollArea::horizontal().id_salt("draft_timeline").show(ui, |ui| {
+        egui::Grid::new("draft_timeline_grid").spacing([6.0, 4.0]).show(ui, |ui| {
+            ui.label("");
+            for pick_ban in picks_bans {
+                ui.weak(format!("{}", pick_ban.order + 1));
+            }
+            ui.end_row();
+
+            for (is_radiant, side) in [(true, "天辉"), (false, "夜魇")] {
+                ui.strong(side);
+                for pick_ban in picks_bans {
+                    if pick_ban.is_radiant() == is_radiant {
+                        draft_cell(ui, pick_ban, constant);
+                    } else {
+                        ui.label("");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
+
+fn draft_cell(
+    ui: &mut egui::Ui,
+    pick_ban: &PickBan,
+    constant: &GameConstant,
+) {
+    let color = if pick_ban.is_radiant() { RADIANT_COLOR } else { DIRE_COLOR };
+    let hero_name = constant.hero(pick_ban.hero_id).to_string();
+    let (text, hover) = if pick_ban.is_pick {
+        (
+            RichText::new(&hero_name).color(color),
+            format!("Pick #{}: {}", pick_ban.order + 1, hero_name),
+        )
+    } else {
+        (
+            RichText::new(&hero_name).weak().strikethrough(),
+            format!("Ban #{}: {}", pick_ban.order + 1, hero_name),
+        )
+    };
+    egui::Frame::group(ui.style()).inner_margin(4.0).show(ui, |ui| {
+        ui.label(text).on_hover_text(hover);
+    });
+}
@@ -0,0 +1,416 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+};
+
+use chrono::NaiveDate;
+use common::{
+    data::matches::{GameMode, LobbyType, MatchDetailView},
+    metrics,
+};
+use eframe::egui;
+use egui_extras::DatePickerButton;
+use serde::{Deserialize, Serialize};
+
+use crate::{constant::GameConstant, notes::NoteBook};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Any,
+    Win,
+    Lose,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortKey {
+    Win,
+    StartTime,
+    Duration,
+    GameMode,
+    Hero,
+    Kills,
+    Deaths,
+    Assists,
+    /// (K + A) / D
+    Kda,
+    NetWorth,
+}
+
+impl SortKey {
+    const ALL: [SortKey; 10] = [
+        SortKey::Win,
+        SortKey::StartTime,
+        SortKey::Duration,
+        SortKey::GameMode,
+        SortKey::Hero,
+        SortKey::Kills,
+        SortKey::Deaths,
+        SortKey::Assists,
+        SortKey::Kda,
+        SortKey::NetWorth,
+    ];
+
+    fn compare(
+        &self,
+        a: &MatchDetailView,
+        b: &MatchDetailView,
+        heroes: &HashMap<i32, String>,
+    ) -> Ordering {
+        let kda = |view: &MatchDetailView| {
+            let player = view.player_detail();
+            metrics::kda(player.kills, player.deaths, player.assists)
+        };
+        match self {
+            SortKey::Win => a.win().cmp(&b.win()),
+            SortKey::StartTime => a.start_time().cmp(&b.start_time()),
+            SortKey::Duration => a.duration().cmp(&b.duration()),
+            SortKey::GameMode => (a.game_mode() as i32).cmp(&(b.game_mode() as i32)),
+            SortKey::Kills => a.player_detail().kills.cmp(&b.player_detail().kills),
+            SortKey::Deaths => a.player_detail().deaths.cmp(&b.player_detail().deaths),
+            SortKey::Assists => a.player_detail().assists.cmp(&b.player_detail().assists),
+            SortKey::Hero => {
+                let hero_name = |view: &MatchDetailView| heroes.get(&view.player_detail().hero_id);
+                hero_name(a).cmp(&hero_name(b))
+            }
+            SortKey::Kda => kda(a).total_cmp(&kda(b)),
+            SortKey::NetWorth => a.player_detail().net_worth.cmp(&b.player_detail().net_worth),
+        }
+    }
+}
+
+/// Hero and item names resolved once per `MatchFilter::apply` instead of once per match or
+/// comparison
+struct Names<'a> {
+    constant: &'a GameConstant,
+    heroes: HashMap<i32, String>,
+    /// Lowercase, for the search
+    heroes_lowercase: HashMap<i32, String>,
+    /// Lowercase, for the search
+    items_lowercase: HashMap<i32, String>,
+}
+
+impl<'a> Names<'a> {
+    fn new(constant: &'a GameConstant) -> Self {
+        Self {
+            constant,
+            heroes: HashMap::new(),
+            heroes_lowercase: HashMap::new(),
+            items_lowercase: HashMap::new(),
+        }
+    }
+
+    /// Names of every hero in `views`, for sorting by hero
+    fn resolve_heroes<'v>(
+        &mut self,
+        views: impl Iterator<Item = &'v MatchDetailView>,
+    ) -> &HashMap<i32, String> {
+        let constant = self.constant;
+        for view in views {
+            let id = view.player_detail().hero_id;
+            self.heroes.entry(id).or_insert_with(|| constant.hero(id).to_string());
+        }
+        &self.heroes
+    }
+
+    fn hero_lowercase(
+        &mut self,
+        id: i32,
+    ) -> &str {
+        let constant = self.constant;
+        self.heroes_lowercase
+            .entry(id)
+            .or_insert_with(|| constant.hero(id).to_string().to_lowercase())
+    }
+
+    fn item_lowercase(
+        &mut self,
+        id: i32,
+    ) -> &str {
+        let constant = self.constant;
+        self.items_lowercase
+            .entry(id)
+            .or_insert_with(|| constant.item(id).to_string().to_lowercase())
+    }
+}
+
+/// Filter bar state of the match table, every condition is optional and they are combined with AND
+#[derive(Clone, PartialEq)]
+pub struct MatchFilter {
+    outcome: Outcome,
+    game_mode: Option<GameMode>,
+    lobby_type: Option<LobbyType>,
+    hero_id: Option<i32>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    /// in minutes
+    min_duration: Option<i32>,
+    /// in minutes
+    max_duration: Option<i32>,
+    /// Tag of the user's match note
+    tag: Option<String>,
+    /// Free-text search on hero and item names and the user's match notes
+    search: String,
+    sort_key: SortKey,
+    descending: bool,
+}
+
+impl Default for MatchFilter {
+    fn default() -> Self {
+        Self {
+            outcome: Outcome::Any,
+            game_mode: None,
+            lobby_type: None,
+            hero_id: None,
+            from: None,
+            to: None,
+            min_duration: None,
+            max_duration: None,
+            tag: None,
+            search: String::new(),
+            sort_key: SortKey::StartTime,
+            descending: true,
+        }
+    }
+}
+
+impl MatchFilter {
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        constant: &GameConstant,
+        notes: &NoteBook,
+    ) {
+        ui.horizontal_wrapped(|ui| {
+            egui::ComboBox::from_id_salt("filter_outcome")
+                .selected_text(format!("{:?}", self.outcome))
+                .show_ui(ui, |ui| {
+                    for outcome in [Outcome::Any, Outcome::Win, Outcome::Lose] {
+                        ui.selectable_value(&mut self.outcome, outcome, format!("{:?}", outcome));
+                    }
+                });
+
+            option_combo(ui, "filter_game_mode", "Game Mode", &mut self.game_mode, &GameMode::ALL);
+            option_combo(ui, "filter_lobby_type", "Lobby Type", &mut self.lobby_type, &LobbyType::ALL);
+
+            let hero_text = self.hero_id.map(|id| constant.hero(id).to_string()).unwrap_or(String::from("Any Hero"));
+            egui::ComboBox::from_id_salt("filter_hero").selected_text(hero_text).show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.hero_id, None, "Any Hero");
+                for (id, name) in constant.heroes() {
+                    ui.selectable_value(&mut self.hero_id, Some(id), name);
+                }
+            });
+
+            optional_date(ui, "filter_from", "From", &mut self.from);
+            optional_date(ui, "filter_to", "To", &mut self.to);
+            optional_minutes(ui, "Min Duration", &mut self.min_duration, 20);
+            optional_minutes(ui, "Max Duration", &mut self.max_duration, 60);
+
+            let tag_text = self.tag.clone().unwrap_or(String::from("Any Tag"));
+            egui::ComboBox::from_id_salt("filter_tag").selected_text(tag_text).show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.tag, None, "Any Tag");
+                for tag in notes.tags() {
+                    ui.selectable_value(&mut self.tag, Some(tag.clone()), tag);
+                }
+            });
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.search)
+                    .hint_text("Search hero / item / note")
+                    .desired_width(150.0),
+            );
+
+            egui::ComboBox::from_id_salt("filter_sort")
+                .selected_text(format!("Sort: {:?}", self.sort_key))
+                .show_ui(ui, |ui| {
+                    for key in SortKey::ALL {
+                        ui.selectable_value(&mut self.sort_key, key, format!("{:?}", key));
+                    }
+                });
+            let order_text = if self.descending { "Desc" } else { "Asc" };
+            ui.toggle_value(&mut self.descending, order_text);
+
+            if ui.button("Reset").clicked() {
+                *self = Self::default();
+            }
+        });
+    }
+
+    /// Sort by `key`, clicking the current key again flips the order
+    pub fn sort_by(
+        &mut self,
+        key: SortKey,
+    ) {
+        if self.sort_key == key {
+            self.descending = !self.descending;
+        } else {
+            self.sort_key = key;
+            self.descending = true;
+        }
+    }
+
+    /// Current sort key and whether it is descending
+    pub fn sort(&self) -> (SortKey, bool) {
+        (self.sort_key, self.descending)
+    }
+
+    pub fn set_sort(
+        &mut self,
+        key: SortKey,
+        descending: bool,
+    ) {
+        self.sort_key = key;
+        self.descending = descending;
+    }
+
+    /// Column header text with an arrow when the table is sorted by `key`
+    pub fn header_text(
+        &self,
+        key: SortKey,
+        text: &str,
+    ) -> String {
+        match (self.sort_key == key, self.descending) {
+            (true, true) => format!("{} ⬇", text),
+            (true, false) => format!("{} ⬆", text),
+            (false, _) => text.to_owned(),
+        }
+    }
+
+    /// Indices into `matches` that pass the filter, in display order
+    pub fn apply(
+        &self,
+        matches: &VecDeque<MatchDetailView>,
+        constant: &GameConstant,
+        notes: &NoteBook,
+    ) -> Vec<usize> {
+        let mut names = Names::new(constant);
+        let search = self.search.trim().to_lowercase();
+        let mut indices = matches
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| self.matches(m, &search, &mut names, notes))
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+
+        let heroes = match self.sort_key {
+            SortKey::Hero => names.resolve_heroes(indices.iter().map(|i| &matches[*i])),
+            _ => &names.heroes,
+        };
+        indices.sort_by(|a, b| {
+            let ordering = self.sort_key.compare(&matches[*a], &matches[*b], heroes);
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        indices
+    }
+
+    /// `search` is the trimmed, lowercase search text
+    fn matches(
+        &self,
+        view: &MatchDetailView,
+        search: &str,
+        names: &mut Names<'_>,
+        notes: &NoteBook,
+    ) -> bool {
+        let player = view.player_detail();
+        let note = notes.get(view.match_id());
+
+        match self.outcome {
+            Outcome::Win if !view.win() => return false,
+            Outcome::Lose if view.win() => return false,
+            _ => {}
+        }
+        if self.game_mode.is_some_and(|mode| mode != view.game_mode()) {
+            return false;
+        }
+        if self.lobby_type.is_some_and(|lobby| lobby != view.lobby_type()) {
+            return false;
+        }
+        if self.hero_id.is_some_and(|hero| hero != player.hero_id) {
+            return false;
+        }
+
+        if self.from.is_some() || self.to.is_some() {
+            let Some(date) = chrono::DateTime::from_timestamp(view.start_time(), 0).map(|dt| dt.with_timezone(&chrono::Local).date_naive()) else {
+                return false;
+            };
+            if self.from.is_some_and(|from| date < from) || self.to.is_some_and(|to| date > to) {
+                return false;
+            }
+        }
+
+        if let Some(tag) = &self.tag {
+            if !note.is_some_and(|note| note.tags.contains(tag)) {
+                return false;
+            }
+        }
+
+        let minutes = view.duration() / 60;
+        if self.min_duration.is_some_and(|min| minutes < min) || self.max_duration.is_some_and(|max| minutes > max) {
+            return false;
+        }
+
+        if !search.is_empty() {
+            let hero_hit = names.hero_lowercase(player.hero_id).contains(search);
+            let item_hit = player
+                .items()
+                .into_iter()
+                .chain(player.additional_units.iter().flat_map(|unit| unit.items()))
+                .any(|id| names.item_lowercase(id).contains(search));
+            let note_hit = note.is_some_and(|note| note.contains(search));
+            if !hero_hit && !item_hit && !note_hit {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn option_combo<T: Copy + PartialEq + std::fmt::Debug>(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    any_text: &str,
+    value: &mut Option<T>,
+    options: &[T],
+) {
+    let selected = value.map(|v| format!("{:?}", v)).unwrap_or(format!("Any {}", any_text));
+    egui::ComboBox::from_id_salt(id_salt).selected_text(selected).show_ui(ui, |ui| {
+        ui.selectable_value(value, None, format!("Any {}", any_text));
+        for option in options {
+            ui.selectable_value(value, Some(*option), format!("{:?}", option));
+        }
+    });
+}
+
+fn optional_date(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    label: &str,
+    value: &mut Option<NaiveDate>,
+) {
+    let mut enabled = value.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *value = enabled.then(|| chrono::Local::now().date_naive());
+    }
+    if let Some(date) = value {
+        ui.add(DatePickerButton::new(date).id_salt(id_salt));
+    }
+}
+
+fn optional_minutes(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<i32>,
+    default: i32,
+) {
+    let mut enabled = value.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *value = enabled.then_some(default);
+    }
+    if let Some(minutes) = value {
+        ui.add(egui::DragValue::new(minutes).range(0..=180).suffix("m"));
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use common::goal::{Comparison, Goal, GoalMetric, GoalProgress};
+use eframe::egui;
+use egui::{mutex::RwLock, Color32, RichText};
+
+use super::{Component, DIRE_COLOR, RADIANT_COLOR};
+use crate::{archive::MatchArchive, constant::GameConstant, state::AppState};
+
+/// Goals kept in `AppState::goals`, each with its progress over the latest matches
+pub struct GoalsPanel {
+    archive: Arc<RwLock<MatchArchive>>,
+    /// Goal being added, pushed to the state on "Add"
+    draft: Goal,
+}
+
+impl GoalsPanel {
+    pub fn new(archive: Arc<RwLock<MatchArchive>>) -> Self {
+        Self {
+            archive,
+            draft: Goal::default(),
+        }
+    }
+}
+
+impl Component for GoalsPanel {
+    fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut AppState,
+        constant: &Arc<RwLock<GameConstant>>,
+    ) {
+        // Newest first, shared with the other panels
+        let views = self.archive.read().views(state.account_id);
+        let constant = constant.read();
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("目标");
+            ui.separator();
+
+            ui.group(|ui| {
+                goal_editor(ui, "draft_goal", &mut self.draft, &constant);
+                if ui.button("Add").clicked() {
+                    state.goals.push(std::mem::take(&mut self.draft));
+                }
+            });
+
+            let mut removed = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (index, goal) in state.goals.iter().enumerate() {
+                    ui.separator();
+                    let progress = goal.evaluate(views.iter());
+                    ui.horizontal(|ui| {
+                        ui.strong(if goal.name.is_empty() { goal.condition() } else { goal.name.clone() });
+                        if !goal.name.is_empty() {
+                            ui.weak(goal.condition());
+                        }
+                        if !goal.heroes.is_empty() {
+                            let heroes = goal.heroes.iter().map(|id| constant.hero(*id).to_string()).collect::<Vec<_>>();
+                            ui.weak(heroes.join(", "));
+                        }
+                        if ui.small_button("Delete").clicked() {
+                            removed = Some(index);
+                        }
+                    });
+                    progress_ui(ui, goal, &progress);
+                }
+            });
+            if let Some(index) = removed {
+                state.goals.remove(index);
+            }
+        });
+    }
+}
+
+fn goal_editor(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    goal: &mut Goal,
+    constant: &GameConstant,
+) {
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut goal.name).hint_text("名称").desired_width(100.0));
+        egui::ComboBox::from_id_salt(format!("{}_metric", id_salt))
+            .selected_text(goal.metric.label())
+            .show_ui(ui, |ui| {
+                for metric in GoalMetric::ALL {
+                    ui.selectable_value(&mut goal.metric, metric, metric.label());
+                }
+            });
+        ui.selectable_value(&mut goal.comparison, Comparison::AtLeast, Comparison::AtLeast.symbol());
+        ui.selectable_value(&mut goal.comparison, Comparison::AtMost, Comparison::AtMost.symbol());
+        ui.add(egui::DragValue::new(&mut goal.target).speed(0.1));
+        ui.label("最近");
+        ui.add(egui::DragValue::new(&mut goal.window).range(1..=100).suffix(" 场"));
+
+        egui::ComboBox::from_id_salt(format!("{}_heroes", id_salt))
+            .selected_text(if goal.heroes.is_empty() {
+                "所有英雄".to_owned()
+            } else {
+                format!("{} 个英雄", goal.heroes.len())
+            })
+            .show_ui(ui, |ui| {
+                for (hero_id, name) in constant.heroes() {
+                    let mut selected = goal.heroes.contains(&hero_id);
+                    if ui.checkbox(&mut selected, name).changed() {
+                        if selected {
+                            goal.heroes.push(hero_id);
+                        } else {
+                            goal.heroes.retain(|id| *id != hero_id);
+                        }
+                    }
+                }
+            });
+    });
+}
+
+fn progress_ui(
+    ui: &mut egui::Ui,
+    goal: &Goal,
+    progress: &GoalProgress,
+) {
+    if progress.history.is_empty() {
+        ui.weak("还没有符合条件的比赛");
+        return;
+    }
+
+    let hits = progress.history.iter().filter(|result| result.hit).count();
+    ui.add(
+        egui::ProgressBar::new(progress.hit_rate())
+            .text(format!("{} / {} 场达成", hits, progress.history.len()))
+            .desired_width(300.0),
+    );
+    let color = if progress.average_hit { RADIANT_COLOR } else { DIRE_COLOR };
+    ui.colored_label(
+        color,
+        format!("平均 {:.1} (目标 {} {})", progress.average, goal.comparison.symbol(), goal.target),
+    );
+    // Oldest on the left, like a timeline
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 2.0;
+        for result in progress.history.iter().rev() {
+            let (text, color) = if result.hit { ("✔", RADIANT_COLOR) } else { ("✖", DIRE_COLOR) };
+            ui.label(RichText::new(text).color(Color32::WHITE).background_color(color))
+                .on_hover_text(format!("{}: {:.1}", result.match_id, result.value));
+        }
+    });
+}
@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+
+use eframe::egui;
+use egui::Vec2;
+use tracing::error;
+
+use crate::{constant::GameConstant, message::Task};
+
+/// Downloaded icons live next to the constants
+pub const ICON_DIR: &str = "config/icons";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IconKind {
+    Hero,
+    Item,
+    Ability,
+}
+
+impl IconKind {
+    fn prefix(&self) -> &'static str {
+        match self {
+            IconKind::Hero => "hero",
+            IconKind::Item => "item",
+            IconKind::Ability => "ability",
+        }
+    }
+
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "hero" => Some(IconKind::Hero),
+            "item" => Some(IconKind::Item),
+            "ability" => Some(IconKind::Ability),
+            _ => None,
+        }
+    }
+
+    /// Width / height of the images on the CDN
+    fn aspect_ratio(&self) -> f32 {
+        match self {
+            IconKind::Hero => 16.0 / 9.0,
+            IconKind::Item => 88.0 / 64.0,
+            IconKind::Ability => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IconKey {
+    pub kind: IconKind,
+    pub id: i32,
+}
+
+impl IconKey {
+    pub fn hero(id: i32) -> Self {
+        Self { kind: IconKind::Hero, id }
+    }
+
+    pub fn item(id: i32) -> Self {
+        Self { kind: IconKind::Item, id }
+    }
+
+    pub fn ability(id: i32) -> Self {
+        Self { kind: IconKind::Ability, id }
+    }
+
+    pub fn path(&self) -> String {
+        format!("{}/{}_{}.png", ICON_DIR, self.kind.prefix(), self.id)
+    }
+
+    pub fn url<'a>(
+        &self,
+        constant: &'a GameConstant,
+    ) -> Option<&'a String> {
+        let urls = constant.icon_urls();
+        let url = match self.kind {
+            IconKind::Hero => urls.heroes.get(&self.id),
+            IconKind::Item => urls.items.get(&self.id),
+            IconKind::Ability => urls.abilities.get(&self.id),
+        };
+        if url.is_none() {
+            constant.missing_icon_url(self.kind.prefix(), self.id);
+        }
+        url
+    }
+
+    fn from_file_name(name: &str) -> Option<Self> {
+        let (prefix, id) = name.strip_suffix(".png")?.split_once('_')?;
+        Some(Self {
+            kind: IconKind::from_prefix(prefix)?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Tracks which icons are on disk and asks the app to download the missing ones. Anything not
+/// cached (yet) is drawn as text, so the UI keeps working offline.
+pub struct IconCache {
+    available: HashSet<IconKey>,
+    /// Requested in this session, either still downloading or failed. Never requested twice.
+    requested: HashSet<IconKey>,
+    task_tx: std::sync::mpsc::Sender<Task>,
+}
+
+impl IconCache {
+    pub fn new(task_tx: std::sync::mpsc::Sender<Task>) -> Self {
+        let available = std::fs::read_dir(ICON_DIR)
+            .map(|dir| {
+                dir.filter_map(|entry| entry.ok())
+                    .filter_map(|entry| IconKey::from_file_name(&entry.file_name().to_string_lossy()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            available,
+            requested: HashSet::new(),
+            task_tx,
+        }
+    }
+
+    pub fn loaded(
+        &mut self,
+        key: IconKey,
+    ) {
+        self.available.insert(key);
+    }
+
+    /// The icon scaled to `height`, `None` when it is not cached yet
+    pub fn image(
+        &mut self,
+        ui: &egui::Ui,
+        key: IconKey,
+        height: f32,
+        constant: &GameConstant,
+    ) -> Option<egui::Image<'static>> {
+        if self.available.contains(&key) {
+            let size = Vec2::new(height * key.kind.aspect_ratio(), height);
+            return Some(egui::Image::new(format!("file://{}", key.path())).fit_to_exact_size(size));
+        }
+
+        if key.url(constant).is_some() && self.requested.insert(key) {
+            if let Err(e) = self.task_tx.send(Task::DownloadIcon(key)) {
+                error!("Send Task::DownloadIcon error: {}", e);
+            }
+            // Tasks are only picked up on the next frame
+            ui.ctx().request_repaint();
+        }
+        None
+    }
+
+    /// The icon with `text` as tooltip, or just `text` when the icon is not cached
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        key: IconKey,
+        text: &str,
+        height: f32,
+        constant: &GameConstant,
+    ) -> egui::Response {
+        match self.image(ui, key, height, constant) {
+            Some(image) => ui.add(image).on_hover_text(text),
+            None => ui.label(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name_round_trip() {
+        let key = IconKey::item(1466);
+        let path = key.path();
+        let file_name = path.rsplit('/').next().unwrap();
+        assert_eq!(IconKey::from_file_name(file_name), Some(key));
+        assert_eq!(IconKey::from_file_name("unknown_1.png"), None);
+    }
+}
@@ -1,23 +1,52 @@
-mod mapper;
+mod break_reminder;
+mod building;
+mod compare;
+mod draft;
+mod filter;
+mod goals;
+pub mod icon;
+mod notes;
+mod onboarding;
+mod overlay;
 mod panel;
+mod report;
+mod settings;
+mod skill_build;
+mod ui_state;
 
-use std::{collections::HashMap, fmt::Display, sync::Arc};
-
-use eframe::{egui, Result};
-use egui::{
-    mutex::{Mutex, RwLock},
-    FontData, FontDefinitions, FontFamily,
+use std::{
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
+
+use break_reminder::BreakReminder;
+use compare::ComparePanel;
+use eframe::egui;
+use egui::mutex::{Mutex, RwLock};
+use goals::GoalsPanel;
+use icon::{IconKey, ICON_DIR};
+use overlay::OverlayWindow;
 use panel::MainPanel;
+use report::TodayPanel;
 use server::courier::Courier;
+use settings::{font_definitions, SettingsPanel};
 use snafu::ResultExt;
-use tokio::runtime::Runtime;
+use tokio::{runtime::Runtime, task::JoinHandle};
 use tracing::{error, info, warn};
+use ui_state::{UiState, View, UI_STATE_KEY};
 
 use crate::{
-    error::{JsonSnafu, ReadFileSnafu, ServerSnafu, WriteFileSnafu},
+    api::{self, ApiState, FALLBACK_REFRESH_INTERVAL},
+    archive::MatchArchive,
+    constant::GameConstant,
+    error::{ServerSnafu, WriteFileSnafu},
     message::Task,
-    state::AppState,
+    notes::NoteBook,
+    persist::{Persistence, AUTOSAVE_INTERVAL},
+    state::{AppState, Settings},
     ui::panel::LeftPanel,
 };
 
@@ -30,12 +59,16 @@ pub trait Component {
     );
 }
 
-pub fn launch() -> eframe::Result {
+/// `open_match` is selected once the app is up, e.g. from a `watchingir://match/<id>` link
+pub fn launch(open_match: Option<i64>) -> eframe::Result {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default(),
+        persist_window: true,
+        // Keep eframe's storage next to the rest of the config
+        persistence_path: Some("config/app.ron".into()),
         ..Default::default()
     };
-    eframe::run_native("Watchingir", options, Box::new(|cc| Ok(Box::new(App::new(cc)))))
+    eframe::run_native("Watchingir", options, Box::new(move |cc| Ok(Box::new(App::new(cc, open_match)))))
 }
 
 struct App {
@@ -44,38 +77,101 @@ struct App {
     task_rx: std::sync::mpsc::Receiver<Task>,
     left_panel: LeftPanel,
     main_panel: Arc<Mutex<MainPanel>>,
+    settings_panel: SettingsPanel,
+    today_panel: TodayPanel,
+    goals_panel: GoalsPanel,
+    compare_panel: ComparePanel,
     courier: Arc<Courier>,
     constant: Arc<RwLock<GameConstant>>,
+    archive: Arc<RwLock<MatchArchive>>,
+    persistence: Persistence,
+    /// Refreshed every frame, written to eframe's storage on `save`
+    ui_state: UiState,
+    /// Settings in effect, compared against `state.settings` every frame to apply the changes
+    applied_settings: Settings,
+    last_refresh: Instant,
+    api_state: ApiState,
+    /// Running local API server, see `Settings::api_enabled`
+    api_server: Option<JoinHandle<()>>,
+    overlay: OverlayWindow,
+    break_reminder: BreakReminder,
 }
 
-impl App {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        // Get font from: https://github.com/lxgw/LxgwWenKai
-        let mut fonts = FontDefinitions::default();
-        fonts.font_data.insert(
-            "kx_font".to_owned(),
-            Arc::new(FontData::from_static(include_bytes!("../../../../assets/LXGWWenKai-Regular.ttf"))),
-        );
+const RADIANT_COLOR: egui::Color32 = egui::Color32::from_rgb(92, 184, 92);
+const DIRE_COLOR: egui::Color32 = egui::Color32::from_rgb(217, 83, 79);
 
-        fonts.families.get_mut(&FontFamily::Proportional).unwrap().push("kx_font".to_owned());
-
-        cc.egui_ctx.set_fonts(fonts);
+/// Matches requested per `Task::LoadMoreMatches`
+const HISTORY_PAGE_SIZE: i32 = 10;
 
+impl App {
+    fn new(
+        cc: &eframe::CreationContext<'_>,
+        open_match: Option<i64>,
+    ) -> Self {
         let state = AppState::try_from_config().unwrap_or_default();
         info!("Loading AppState: {:?}", state);
-        let courier = Courier::default();
+        let settings = state.settings.clone();
+        cc.egui_ctx.set_fonts(font_definitions(settings.font_path.as_deref()));
+        cc.egui_ctx.set_theme(settings.theme);
+        cc.egui_ctx.set_zoom_factor(settings.ui_scale);
+        egui_extras::install_image_loaders(&cc.egui_ctx);
+
+        let courier = Courier::with_steam_api(settings.steam_api());
         let (tx, rx) = std::sync::mpsc::channel();
         let constant = GameConstant::from_config().unwrap_or_default();
         info!("Loading GameConstant: {}", constant);
-        Self {
+        let archive = MatchArchive::from_config().unwrap_or_default();
+        info!("Loading MatchArchive: {} matches", archive.len());
+        let notes = Arc::new(RwLock::new(NoteBook::from_config().unwrap_or_default()));
+        let ui_state: UiState = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, UI_STATE_KEY))
+            .unwrap_or_default();
+        info!("Loading UiState: {:?}", ui_state);
+        if let Some(match_id) = open_match {
+            if let Err(e) = tx.send(Task::OpenMatch(match_id)) {
+                error!("Send Task::OpenMatch error: {}", e);
+            }
+        }
+        let mut main_panel = MainPanel::new(tx.clone(), Arc::clone(&notes));
+        main_panel.update_match_detail(archive.views(state.account_id), archive.spectated(state.account_id));
+        main_panel.restore(&ui_state);
+        let constant = Arc::new(RwLock::new(constant));
+        let archive = Arc::new(RwLock::new(archive));
+        let api_state = ApiState {
+            archive: Arc::clone(&archive),
+            constant: Arc::clone(&constant),
+            notes: Arc::clone(&notes),
+            account_id: Arc::new(AtomicI64::new(state.account_id)),
+        };
+        let persistence = Persistence::new(&state, Arc::clone(&constant), Arc::clone(&archive), Arc::clone(&notes));
+        let mut app = Self {
             rt: tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap(),
             state,
             task_rx: rx,
-            left_panel: LeftPanel::new(tx.clone()),
-            main_panel: Arc::new(Mutex::new(MainPanel::new(tx))),
+            left_panel: LeftPanel::new(tx, ui_state.view, ui_state.overlay_open),
+            main_panel: Arc::new(Mutex::new(main_panel)),
+            settings_panel: SettingsPanel::new(settings.font_path.as_deref()),
+            today_panel: TodayPanel::new(Arc::clone(&archive), Arc::clone(&notes)),
+            goals_panel: GoalsPanel::new(Arc::clone(&archive)),
+            compare_panel: ComparePanel::new(Arc::clone(&archive)),
             courier: Arc::new(courier),
-            constant: Arc::new(RwLock::new(constant)),
+            constant,
+            archive,
+
+            persistence,
+            ui_state,
+            applied_settings: settings,
+            last_refresh: Instant::now(),
+            api_state,
+            api_server: None,
+            overlay: OverlayWindow::default(),
+            break_reminder: BreakReminder::default(),
+        };
+        if app.state.settings.api_enabled {
+            app.start_api_server();
         }
+        app
     }
 }
 
@@ -102,21 +198,174 @@ impl eframe::App for App {
                         }
                     }
 
-                    self.latest_match_detail();
+                    self.last_refresh = Instant::now();
+                    self.latest_match_detail(ctx);
+                }
+                Task::LoadMoreMatches => {
+                    self.load_more_matches();
+                }
+                Task::DownloadIcon(key) => {
+                    self.download_icon(ctx, key);
+                }
+                Task::OpenMatch(match_id) => {
+                    self.open_match(ctx, match_id);
+                }
+                Task::CompareMatch { match_id, player_index } => {
+                    self.compare_panel.select_match(match_id, player_index);
+                    self.left_panel.set_view(View::Compare);
                 }
             }
         }
 
+        if self.constant.read().take_refresh_request() {
+            info!("Unknown ids in game constant, fetching from remote");
+            self.fetch_constant();
+        }
+
+        self.auto_refresh(ctx);
+
         self.left_panel.ui(ctx, &mut self.state, &self.constant);
-        self.main_panel.lock().ui(ctx, &mut self.state, &self.constant);
+        if self.state.settings.break_reminder {
+            self.break_reminder
+                .show(ctx, &self.archive.read(), self.state.account_id, self.state.settings.tilt_threshold);
+        }
+        match self.left_panel.view() {
+            View::Today => self.today_panel.ui(ctx, &mut self.state, &self.constant),
+            View::Goals => self.goals_panel.ui(ctx, &mut self.state, &self.constant),
+            View::Compare => self.compare_panel.ui(ctx, &mut self.state, &self.constant),
+            View::Settings => self.settings_panel.ui(ctx, &mut self.state, &self.constant),
+            _ => self.main_panel.lock().ui(ctx, &mut self.state, &self.constant),
+        }
+        self.apply_settings(ctx);
+        if self.left_panel.overlay_open() {
+            let open = self.overlay.show(
+                ctx,
+                &self.archive.read(),
+                &self.constant.read(),
+                self.state.account_id,
+                self.state.settings.overlay_results,
+            );
+            self.left_panel.set_overlay_open(open);
+        }
+        self.api_state.account_id.store(self.state.account_id, Ordering::Relaxed);
+
+        self.ui_state.view = self.left_panel.view();
+        self.ui_state.overlay_open = self.left_panel.overlay_open();
+        self.main_panel.lock().store(&mut self.ui_state);
+
+        // eframe only calls `save` from a frame
+        ctx.request_repaint_after(AUTOSAVE_INTERVAL);
+    }
+
+    fn save(
+        &mut self,
+        storage: &mut dyn eframe::Storage,
+    ) {
+        eframe::set_value(storage, UI_STATE_KEY, &self.ui_state);
+        self.persistence.save(&self.state);
+    }
+
+    fn on_exit(
+        &mut self,
+        _gl: Option<&eframe::glow::Context>,
+    ) {
+        info!("Exiting, save everything to config/");
+        self.persistence.flush(&self.state);
+    }
+
+    fn auto_save_interval(&self) -> std::time::Duration {
+        AUTOSAVE_INTERVAL
     }
 }
 
 impl App {
+    // settings
+
+    fn apply_settings(
+        &mut self,
+        ctx: &egui::Context,
+    ) {
+        let settings = &self.state.settings;
+        let applied = &self.applied_settings;
+        if settings == applied {
+            return;
+        }
+
+        if settings.theme != applied.theme {
+            ctx.set_theme(settings.theme);
+        }
+        if settings.ui_scale != applied.ui_scale {
+            ctx.set_zoom_factor(settings.ui_scale);
+        }
+        if settings.font_path != applied.font_path {
+            ctx.set_fonts(font_definitions(settings.font_path.as_deref()));
+        }
+        if settings.steam_api() != applied.steam_api() {
+            info!("Switch Steam API to {}", settings.steam_api());
+            self.courier = Arc::new(Courier::with_steam_api(settings.steam_api()));
+        }
+        let language_changed = settings.language != applied.language;
+        let api_changed = (settings.api_enabled, settings.api_port) != (applied.api_enabled, applied.api_port);
+
+        self.applied_settings = self.state.settings.clone();
+        if language_changed {
+            info!("Constant language changed, fetching from remote");
+            self.fetch_constant();
+        }
+        if api_changed {
+            self.stop_api_server();
+            if self.state.settings.api_enabled {
+                self.start_api_server();
+            }
+        }
+    }
+
+    // local api
+
+    fn start_api_server(&mut self) {
+        let api_state = self.api_state.clone();
+        let port = self.state.settings.api_port;
+        self.api_server = Some(self.rt.spawn(async move {
+            if let Err(e) = api::serve(api_state, port).await {
+                error!("Local API stopped: {}", e);
+            }
+        }));
+    }
+
+    fn stop_api_server(&mut self) {
+        if let Some(handle) = self.api_server.take() {
+            info!("Stop serving the local API");
+            handle.abort();
+        }
+    }
+
+    /// Fetch the latest match every `refresh_interval` minutes, the overlay and the local API keep
+    /// it on so a new match shows up without touching the app
+    fn auto_refresh(
+        &mut self,
+        ctx: &egui::Context,
+    ) {
+        let minutes = match self.state.settings.refresh_interval {
+            0 if self.left_panel.overlay_open() || self.state.settings.api_enabled => FALLBACK_REFRESH_INTERVAL,
+            0 => return,
+            minutes => minutes,
+        };
+
+        let interval = Duration::from_secs(u64::from(minutes) * 60);
+        let elapsed = self.last_refresh.elapsed();
+        if elapsed >= interval {
+            self.last_refresh = Instant::now();
+            self.latest_match_detail(ctx);
+            ctx.request_repaint_after(interval);
+        } else {
+            ctx.request_repaint_after(interval - elapsed);
+        }
+    }
+
     // constant
 
     fn constant_ready(&self) -> bool {
-        self.constant.read().is_loaded
+        self.constant.read().is_loaded()
     }
 
     fn set_constant(
@@ -126,19 +375,29 @@ impl App {
         *self.constant.write() = constant;
     }
 
-    #[tracing::instrument(skip(self))]
-    fn latest_match_detail(&mut self) {
+    #[tracing::instrument(skip(self, ctx))]
+    fn latest_match_detail(
+        &mut self,
+        ctx: &egui::Context,
+    ) {
         let courier = Arc::clone(&self.courier);
         let steam_api_key = self.state.steam_api_key.clone();
         let account_id = self.state.account_id;
         let main_panel = Arc::clone(&self.main_panel);
+        let archive = Arc::clone(&self.archive);
+        let ctx = ctx.clone();
         self.rt.spawn(async move {
-            let res = courier.latest_match_detail(&steam_api_key, account_id).await.context(ServerSnafu);
-            match res {
-                Ok(match_detail_response) => {
-                    let view = match_detail_response.views(account_id);
-                    let mut guard = main_panel.lock();
-                    guard.update_match_detail(view);
+            let res = courier.latest_match_detail(&steam_api_key, account_id).await;
+            main_panel.lock().set_private_account(res.as_ref().is_err_and(|e| e.is_private_profile()));
+            match res.context(ServerSnafu) {
+                Ok(matches) => {
+                    let (views, spectated) = {
+                        let mut guard = archive.write();
+                        guard.insert(matches);
+                        (guard.views(account_id), guard.spectated(account_id))
+                    };
+                    main_panel.lock().update_match_detail(views, spectated);
+                    ctx.request_repaint();
                 }
                 Err(e) => {
                     error!("Failed to get match detail: {}", e);
@@ -147,92 +406,128 @@ impl App {
         });
     }
 
-    fn fetch_constant(&mut self) {
+    #[tracing::instrument(skip(self))]
+    fn load_more_matches(&mut self) {
         let courier = Arc::clone(&self.courier);
-        let key = self.state.stratz_api_key.clone();
-        let game_constant = Arc::clone(&self.constant);
+        let steam_api_key = self.state.steam_api_key.clone();
+        let account_id = self.state.account_id;
+        let main_panel = Arc::clone(&self.main_panel);
+        let archive = Arc::clone(&self.archive);
+        // `start_at_match_id` is inclusive, step over the oldest match we already have
+        let start_at_match_id = archive.read().oldest_match_id(account_id).map(|id| id - 1);
         self.rt.spawn(async move {
-            let res = courier.constant(&key).await;
-            match res {
-                Ok(constant) => {
-                    let (items, heroes) = constant.spilt();
-                    let mut guard = game_constant.write();
-                    guard.items_map = items;
-                    guard.heroes_map = heroes;
-                    guard.is_loaded = true;
-                    info!("Game constant fetched successfully");
+            let res = courier
+                .match_history(&steam_api_key, account_id, start_at_match_id, HISTORY_PAGE_SIZE)
+                .await;
+            main_panel.lock().set_private_account(res.as_ref().is_err_and(|e| e.is_private_profile()));
+            match res.context(ServerSnafu) {
+                Ok(matches) => {
+                    let (views, spectated) = {
+                        let mut guard = archive.write();
+                        let inserted = guard.insert(matches);
+                        info!("Loaded {} more matches", inserted);
+                        (guard.views(account_id), guard.spectated(account_id))
+                    };
+                    main_panel.lock().update_match_detail(views, spectated);
                 }
                 Err(e) => {
-                    error!("Failed to get constant: {:?}", e);
+                    error!("Failed to load more matches: {}", e);
                 }
             }
         });
     }
-}
-
-#[derive(Default)]
-pub struct GameConstant {
-    items_map: HashMap<i32, String>,
-    heroes_map: HashMap<i32, String>,
-    is_loaded: bool,
-}
-
-impl Display for GameConstant {
-    fn fmt(
-        &self,
-        f: &mut std::fmt::Formatter<'_>,
-    ) -> std::fmt::Result {
-        write!(
-            f,
-            "GameConstant {{ items: {}, heroes: {}, is_loaded: {} }}",
-            self.items_map.len(),
-            self.heroes_map.len(),
-            self.is_loaded
-        )
-    }
-}
 
-impl Drop for GameConstant {
-    fn drop(&mut self) {
-        info!("Drop GameConstant, write to config/items.json and config/heroes.json");
-        if let Err(e) = self.save_before_drop() {
-            error!("Save game constant error: {}", e);
+    #[tracing::instrument(skip(self, ctx))]
+    fn open_match(
+        &mut self,
+        ctx: &egui::Context,
+        match_id: i64,
+    ) {
+        self.left_panel.set_view(View::LatestMatches);
+        if self.archive.read().get(match_id).is_some() {
+            self.main_panel.lock().open_match(match_id);
+            return;
         }
-    }
-}
 
-impl GameConstant {
-    pub fn from_config() -> Result<Self, crate::Error> {
-        let (items_map, heroes_map) = Self::read_json()?;
-
-        Ok(Self {
-            items_map,
-            heroes_map,
-            is_loaded: true,
-        })
+        let courier = Arc::clone(&self.courier);
+        let steam_api_key = self.state.steam_api_key.clone();
+        let account_id = self.state.account_id;
+        let main_panel = Arc::clone(&self.main_panel);
+        let archive = Arc::clone(&self.archive);
+        let ctx = ctx.clone();
+        self.rt.spawn(async move {
+            let res = courier.match_by_id(&steam_api_key, account_id, match_id).await.context(ServerSnafu);
+            match res {
+                Ok(match_detail) => {
+                    let (views, spectated) = {
+                        let mut guard = archive.write();
+                        guard.insert(vec![match_detail]);
+                        (guard.views(account_id), guard.spectated(account_id))
+                    };
+                    let mut main_panel = main_panel.lock();
+                    main_panel.update_match_detail(views, spectated);
+                    main_panel.open_match(match_id);
+                    ctx.request_repaint();
+                }
+                Err(e) => {
+                    error!("Failed to open match {}: {}", match_id, e);
+                }
+            }
+        });
     }
 
-    fn read_json() -> Result<(HashMap<i32, String>, HashMap<i32, String>), crate::Error> {
-        let items_json = std::fs::read_to_string("config/items.json").context(ReadFileSnafu { filename: "items.json" })?;
-        let heroes_json = std::fs::read_to_string("config/heroes.json").context(ReadFileSnafu { filename: "heroes.json" })?;
-
-        let items: HashMap<i32, String> = serde_json::from_str(&items_json).context(JsonSnafu)?;
-        let heroes: HashMap<i32, String> = serde_json::from_str(&heroes_json).context(JsonSnafu)?;
-
-        Ok((items, heroes))
+    fn download_icon(
+        &mut self,
+        ctx: &egui::Context,
+        key: IconKey,
+    ) {
+        let Some(url) = key.url(&self.constant.read()).cloned() else {
+            return;
+        };
+        let courier = Arc::clone(&self.courier);
+        let main_panel = Arc::clone(&self.main_panel);
+        let ctx = ctx.clone();
+        self.rt.spawn(async move {
+            let res = courier.download(&url).await.context(ServerSnafu);
+            match res {
+                Ok(bytes) => {
+                    let path = key.path();
+                    let res = std::fs::create_dir_all(ICON_DIR)
+                        .and_then(|_| std::fs::write(&path, bytes))
+                        .context(WriteFileSnafu { filename: path });
+                    match res {
+                        Ok(_) => {
+                            main_panel.lock().icon_loaded(key);
+                            ctx.request_repaint();
+                        }
+                        Err(e) => {
+                            error!("Failed to save icon: {}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to download icon {}: {}", url, e);
+                }
+            }
+        });
     }
 
-    fn save_before_drop(&self) -> Result<(), crate::Error> {
-        let items = serde_json::to_string_pretty(&self.items_map).context(JsonSnafu)?;
-        let heroes = serde_json::to_string_pretty(&self.heroes_map).context(JsonSnafu)?;
-
-        std::fs::write("config/items.json", items).context(WriteFileSnafu {
-            filename: "config/items.json",
-        })?;
-        std::fs::write("config/heroes.json", heroes).context(WriteFileSnafu {
-            filename: "config/heroes.json",
-        })?;
-
-        Ok(())
+    fn fetch_constant(&mut self) {
+        let courier = Arc::clone(&self.courier);
+        let key = self.state.stratz_api_key.clone();
+        let language = self.state.settings.language;
+        let game_constant = Arc::clone(&self.constant);
+        self.rt.spawn(async move {
+            let res = courier.constant(&key, language).await;
+            match res {
+                Ok(constant) => {
+                    game_constant.write().update(constant);
+                    info!("Game constant fetched successfully");
+                }
+                Err(e) => {
+                    error!("Failed to get constant: {:?}", e);
+                }
+            }
+        });
     }
 }
@@ -0,0 +1,77 @@
+use eframe::egui;
+use egui::Id;
+
+use crate::notes::{NoteBook, MAX_RATING};
+
+/// Stars, tags and free text of `match_id`, written back to `notes` on every change
+pub fn note_editor(
+    ui: &mut egui::Ui,
+    match_id: i64,
+    notes: &mut NoteBook,
+) {
+    let mut note = notes.get(match_id).cloned().unwrap_or_default();
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label("评分:");
+        for star in 1..=MAX_RATING {
+            let text = if star <= note.rating { "★" } else { "☆" };
+            if ui.add(egui::Button::new(text).frame(false)).clicked() {
+                // Clicking the current rating again clears it
+                note.rating = if note.rating == star { 0 } else { star };
+                changed = true;
+            }
+        }
+    });
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("标签:");
+        let mut removed = None;
+        for (index, tag) in note.tags.iter().enumerate() {
+            if ui.small_button(format!("{} ✖", tag)).on_hover_text("Remove").clicked() {
+                removed = Some(index);
+            }
+        }
+        if let Some(index) = removed {
+            note.tags.remove(index);
+            changed = true;
+        }
+
+        egui::ComboBox::from_id_salt(("note_tags", match_id))
+            .selected_text("+")
+            .show_ui(ui, |ui| {
+                for tag in notes.tags() {
+                    if !note.tags.contains(&tag) && ui.selectable_label(false, &tag).clicked() {
+                        note.tags.push(tag);
+                        changed = true;
+                    }
+                }
+            });
+
+        let id = Id::new(("new_tag", match_id));
+        let mut new_tag = ui.data_mut(|data| data.get_temp::<String>(id)).unwrap_or_default();
+        let response = ui.add(egui::TextEdit::singleline(&mut new_tag).hint_text("New tag").desired_width(100.0));
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let tag = new_tag.trim().to_owned();
+            if !tag.is_empty() && !note.tags.contains(&tag) {
+                note.tags.push(tag);
+                changed = true;
+            }
+            new_tag.clear();
+        }
+        ui.data_mut(|data| data.insert_temp(id, new_tag));
+    });
+
+    changed |= ui
+        .add(
+            egui::TextEdit::multiline(&mut note.text)
+                .hint_text("笔记")
+                .desired_rows(3)
+                .desired_width(f32::INFINITY),
+        )
+        .changed();
+
+    if changed {
+        notes.set(match_id, note);
+    }
+}
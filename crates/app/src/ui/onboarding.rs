@@ -0,0 +1,30 @@
+use eframe::egui;
+
+pub enum OnboardingAction {
+    Recheck,
+    Dismiss,
+}
+
+/// How to turn on "Expose Public Match Data", shown while the tracked account's history is private
+pub fn public_data_onboarding(ui: &mut egui::Ui) -> Option<OnboardingAction> {
+    let mut action = None;
+    ui.group(|ui| {
+        ui.heading("需要公开比赛数据");
+        ui.label("Steam API 读不到当前账号的比赛记录, Dota 2 客户端里的「公开比赛数据」没有开启。");
+        ui.add_space(10.0);
+        ui.label("1. 打开 Dota 2, 进入 设置 → 选项 → 社交");
+        ui.label("2. 勾选「公开比赛数据」(Expose Public Match Data)");
+        ui.label("3. 再完成一场比赛, 设置之后的比赛才会公开");
+        ui.label("4. 点击「重新检查」");
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            if ui.button("重新检查").clicked() {
+                action = Some(OnboardingAction::Recheck);
+            }
+            if ui.button("先看已保存的比赛").clicked() {
+                action = Some(OnboardingAction::Dismiss);
+            }
+        });
+    });
+    action
+}
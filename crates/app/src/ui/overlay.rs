@@ -0,0 +1,86 @@
+use chrono::NaiveDate;
+use eframe::egui;
+use egui::{Color32, RichText, ViewportBuilder, ViewportCommand, ViewportId};
+
+use super::{DIRE_COLOR, RADIANT_COLOR};
+use crate::{archive::MatchArchive, constant::GameConstant, overlay::OverlaySummary};
+
+/// Borderless, always-on-top window for capturing in OBS
+#[derive(Default)]
+pub struct OverlayWindow {
+    /// Archive generation, account, date and pip count behind `summary`
+    built_from: Option<(u64, i64, NaiveDate, usize)>,
+    summary: OverlaySummary,
+}
+
+impl OverlayWindow {
+    /// Show the overlay viewport, `false` once the user closed it
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        archive: &MatchArchive,
+        constant: &GameConstant,
+        account_id: i64,
+        results: usize,
+    ) -> bool {
+        let today = chrono::Local::now().date_naive();
+        let key = (archive.generation(), account_id, today, results);
+        if self.built_from != Some(key) {
+            self.summary = OverlaySummary::new(archive.views(account_id).iter(), constant, results, today);
+            self.built_from = Some(key);
+        }
+
+        let mut open = true;
+        let viewport = ViewportBuilder::default()
+            .with_title("Watchingir Overlay")
+            .with_decorations(false)
+            .with_always_on_top()
+            .with_resizable(true)
+            .with_inner_size([360.0, 110.0]);
+        ctx.show_viewport_immediate(ViewportId::from_hash_of("overlay"), viewport, |ctx, _class| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                // No title bar, drag anywhere to move the window
+                let response = ui.interact(ui.max_rect(), ui.id().with("overlay_drag"), egui::Sense::click_and_drag());
+                if response.drag_started() {
+                    ctx.send_viewport_cmd(ViewportCommand::StartDrag);
+                }
+                response.context_menu(|ui| {
+                    if ui.button("Close").clicked() {
+                        open = false;
+                    }
+                });
+                self.summary_ui(ui);
+            });
+            if ctx.input(|i| i.viewport().close_requested()) {
+                open = false;
+            }
+        });
+        open
+    }
+
+    fn summary_ui(
+        &self,
+        ui: &mut egui::Ui,
+    ) {
+        let summary = &self.summary;
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 2.0;
+            for win in &summary.results {
+                let (text, color) = if *win { ("W", RADIANT_COLOR) } else { ("L", DIRE_COLOR) };
+                ui.label(RichText::new(text).strong().color(Color32::WHITE).background_color(color));
+            }
+        });
+        ui.label(RichText::new(format!("今日 {}", summary.today)).strong());
+        if let Some(game) = &summary.last_game {
+            let color = if game.win { RADIANT_COLOR } else { DIRE_COLOR };
+            ui.label(
+                RichText::new(format!(
+                    "{} {}/{}/{} {} GPM",
+                    game.hero, game.kills, game.deaths, game.assists, game.gold_per_min
+                ))
+                .strong()
+                .color(color),
+            );
+        }
+    }
+}
@@ -1,15 +1,23 @@
-use std::{collections::VecDeque, sync::Arc};
+use std::sync::Arc;
 
-use common::data::matches::MatchDetailView;
+use common::{data::matches::MatchDetail, links::ExternalSite};
 use eframe::egui;
 use egui::{mutex::RwLock, Id, Modal};
 use egui_extras::{Column, TableBuilder};
-use tracing::{error, info};
-
-use super::{mapper::id2name, Component, GameConstant};
-use crate::{message::Task, state::AppState};
-
-const MAX_MATCHES: usize = 10;
+use tracing::{error, info, warn};
+
+use super::{
+    building::building_map,
+    draft::draft_timeline,
+    filter::{MatchFilter, SortKey},
+    icon::{IconCache, IconKey},
+    notes::note_editor,
+    onboarding::{public_data_onboarding, OnboardingAction},
+    skill_build::skill_build,
+    ui_state::{UiState, View},
+    Component, DIRE_COLOR, RADIANT_COLOR,
+};
+use crate::{archive::Views, constant::GameConstant, message::Task, notes::NoteBook, persist::Store, state::AppState};
 
 pub struct LeftPanel {
     /// LeftTopPanel
@@ -18,12 +26,38 @@ pub struct LeftPanel {
 }
 
 impl LeftPanel {
-    pub fn new(task_tx: std::sync::mpsc::Sender<Task>) -> Self {
+    pub fn new(
+        task_tx: std::sync::mpsc::Sender<Task>,
+        view: View,
+        overlay_open: bool,
+    ) -> Self {
         Self {
-            menu: Menu::init(),
+            menu: Menu { view, overlay_open },
             _task_tx: task_tx,
         }
     }
+
+    pub fn view(&self) -> View {
+        self.menu.view
+    }
+
+    pub fn set_view(
+        &mut self,
+        view: View,
+    ) {
+        self.menu.view = view;
+    }
+
+    pub fn overlay_open(&self) -> bool {
+        self.menu.overlay_open
+    }
+
+    pub fn set_overlay_open(
+        &mut self,
+        open: bool,
+    ) {
+        self.menu.overlay_open = open;
+    }
 }
 
 impl Component for LeftPanel {
@@ -72,9 +106,37 @@ impl Component for LeftPanel {
     }
 }
 
+/// Which matches the main panel lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// The tracked account's matches
+    Player,
+    /// Archived matches the tracked account didn't play in, e.g. opened by id
+    Spectator,
+}
+
 pub struct MainPanel {
-    matches: VecDeque<MatchDetailView>,
+    mode: Mode,
+    matches: Views,
+    filter: MatchFilter,
+    /// Indices into `matches` that pass `filter`, in display order
+    visible: Vec<usize>,
+    /// Filter, constants and notes behind `visible`, `None` once `matches` changed
+    visible_from: Option<(MatchFilter, bool, u64, u64)>,
     selected_index: Option<usize>,
+    /// Index into the players of the selected match, `None` for the tracked account
+    selected_player: Option<usize>,
+    /// Newest first
+    spectated: Vec<MatchDetail>,
+    spectated_index: Option<usize>,
+    /// The Steam API reported the tracked account's match data as private
+    private_account: bool,
+    /// Text of the "Open match by ID" input
+    open_match_id: String,
+    /// Index into `matches` the table scrolls to on the next frame
+    scroll_to: Option<usize>,
+    icons: IconCache,
+    notes: Arc<RwLock<NoteBook>>,
     task_tx: std::sync::mpsc::Sender<Task>,
 }
 
@@ -90,19 +152,108 @@ impl Component for MainPanel {
 }
 
 impl MainPanel {
-    pub fn new(task_tx: std::sync::mpsc::Sender<Task>) -> Self {
+    pub fn new(
+        task_tx: std::sync::mpsc::Sender<Task>,
+        notes: Arc<RwLock<NoteBook>>,
+    ) -> Self {
         Self {
-            matches: VecDeque::with_capacity(MAX_MATCHES),
+            mode: Mode::Player,
+            matches: Views::default(),
+            filter: MatchFilter::default(),
+            visible: Vec::new(),
+            visible_from: None,
             selected_index: None,
+            selected_player: None,
+            spectated: Vec::new(),
+            spectated_index: None,
+            private_account: false,
+            open_match_id: String::new(),
+            scroll_to: None,
+            icons: IconCache::new(task_tx.clone()),
+            notes,
             task_tx,
         }
     }
 
+    /// Sort order and selection from the last session, call after the archived matches are loaded
+    pub fn restore(
+        &mut self,
+        ui_state: &UiState,
+    ) {
+        self.filter.set_sort(ui_state.sort_key, ui_state.descending);
+        self.selected_index = ui_state
+            .selected_match_id
+            .and_then(|id| self.matches.iter().position(|m| m.match_id() == id));
+    }
+
+    /// Record the sort order and selection into `ui_state`
+    pub fn store(
+        &self,
+        ui_state: &mut UiState,
+    ) {
+        (ui_state.sort_key, ui_state.descending) = self.filter.sort();
+        ui_state.selected_match_id = self.selected_index.map(|index| self.matches[index].match_id());
+    }
+
+    /// Select an archived match, in spectator mode when the tracked account didn't play in it
+    pub fn open_match(
+        &mut self,
+        match_id: i64,
+    ) {
+        if let Some(index) = self.matches.iter().position(|m| m.match_id() == match_id) {
+            self.focus(index);
+        } else if let Some(index) = self.spectated.iter().position(|m| m.match_id() == match_id) {
+            info!("Match {} is not in the tracked account's history, spectating", match_id);
+            self.mode = Mode::Spectator;
+            self.spectated_index = Some(index);
+            self.selected_player = None;
+        } else {
+            warn!("Match {} is not archived", match_id);
+        }
+    }
+
+    pub fn set_private_account(
+        &mut self,
+        private_account: bool,
+    ) {
+        self.private_account = private_account;
+    }
+
+    /// Select `matches[index]` and scroll to it, clearing any filter that hides it
+    fn focus(
+        &mut self,
+        index: usize,
+    ) {
+        let (sort_key, descending) = self.filter.sort();
+        self.filter = MatchFilter::default();
+        self.filter.set_sort(sort_key, descending);
+        self.mode = Mode::Player;
+        self.selected_index = Some(index);
+        self.selected_player = None;
+        self.scroll_to = Some(index);
+    }
+
     pub fn update_match_detail(
         &mut self,
-        matches: VecDeque<MatchDetailView>,
+        matches: Views,
+        spectated: Vec<MatchDetail>,
     ) {
+        // Keep the same match selected when the list is reloaded
+        let selected = self.selected_index.map(|index| self.matches[index].match_id());
         self.matches = matches;
+        self.visible_from = None;
+        self.selected_index = selected.and_then(|id| self.matches.iter().position(|m| m.match_id() == id));
+
+        let selected = self.spectated_index.map(|index| self.spectated[index].match_id());
+        self.spectated = spectated;
+        self.spectated_index = selected.and_then(|id| self.spectated.iter().position(|m| m.match_id() == id));
+    }
+
+    pub fn icon_loaded(
+        &mut self,
+        key: IconKey,
+    ) {
+        self.icons.loaded(key);
     }
 
     fn trigger_update_match_detail(&mut self) {
@@ -111,6 +262,31 @@ impl MainPanel {
         }
     }
 
+    fn trigger_open_match(
+        &mut self,
+        match_id: i64,
+    ) {
+        if let Err(e) = self.task_tx.send(Task::OpenMatch(match_id)) {
+            error!("Send Task::OpenMatch error: {}", e);
+        }
+    }
+
+    fn trigger_compare_match(
+        &mut self,
+        match_id: i64,
+        player_index: usize,
+    ) {
+        if let Err(e) = self.task_tx.send(Task::CompareMatch { match_id, player_index }) {
+            error!("Send Task::CompareMatch error: {}", e);
+        }
+    }
+
+    fn trigger_load_more_matches(&mut self) {
+        if let Err(e) = self.task_tx.send(Task::LoadMoreMatches) {
+            error!("Send Task::LoadMoreMatches error: {}", e);
+        }
+    }
+
     fn table_ui(
         &mut self,
         ctx: &egui::Context,
@@ -118,9 +294,52 @@ impl MainPanel {
     ) {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.vertical(|ui| {
-                ui.label("Latest Match Details");
-                if ui.button("Update").clicked() {
-                    self.trigger_update_match_detail();
+                ui.label("Match History");
+                ui.horizontal(|ui| {
+                    let mode = self.mode;
+                    ui.selectable_value(&mut self.mode, Mode::Player, "我的比赛");
+                    ui.selectable_value(&mut self.mode, Mode::Spectator, format!("观战 ({})", self.spectated.len()));
+                    if self.mode != mode {
+                        // The picked player belongs to the other mode's match
+                        self.selected_player = None;
+                    }
+                    ui.separator();
+                    if ui.button("Update").clicked() {
+                        self.trigger_update_match_detail();
+                    }
+                    ui.separator();
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.open_match_id)
+                            .hint_text("Match ID")
+                            .desired_width(120.0),
+                    );
+                    let match_id = self.open_match_id.trim().parse::<i64>().ok();
+                    if ui.add_enabled(match_id.is_some(), egui::Button::new("Open match by ID")).clicked() {
+                        self.trigger_open_match(match_id.unwrap_or_default());
+                    }
+                });
+                ui.separator();
+                if self.mode == Mode::Spectator {
+                    self.spectator_ui(ui, constant);
+                    return;
+                }
+                if self.private_account {
+                    match public_data_onboarding(ui) {
+                        Some(OnboardingAction::Recheck) => self.trigger_update_match_detail(),
+                        Some(OnboardingAction::Dismiss) => self.private_account = false,
+                        None => {}
+                    }
+                    return;
+                }
+                {
+                    let guard = constant.read();
+                    let notes = self.notes.read();
+                    self.filter.ui(ui, &guard, &notes);
+                    let key = (self.filter.clone(), guard.is_loaded(), guard.generation(), notes.generation());
+                    if self.visible_from.as_ref() != Some(&key) {
+                        self.visible = self.filter.apply(&self.matches, &guard, &notes);
+                        self.visible_from = Some(key);
+                    }
                 }
                 ui.separator();
                 egui_extras::StripBuilder::new(ui)
@@ -128,14 +347,21 @@ impl MainPanel {
                     .vertical(|mut strip| {
                         strip.cell(|ui| {
                             egui::ScrollArea::horizontal().show(ui, |ui| {
-                                if !self.matches.is_empty() {
-                                    self.rows(ui);
+                                if !self.visible.is_empty() {
+                                    self.rows(ui, constant);
+                                }
+                            });
+
+                            ui.horizontal(|ui| {
+                                ui.label(format!("{} / {} matches", self.visible.len(), self.matches.len()));
+                                if ui.button("Load More").clicked() {
+                                    self.trigger_load_more_matches();
                                 }
                             });
 
                             ui.add_space(30.0);
 
-                            if self.selected_index.is_some() {
+                            if self.selected_index.is_some_and(|index| self.visible.contains(&index)) {
                                 self.player_detail(ui, constant);
                             }
                         })
@@ -147,11 +373,13 @@ impl MainPanel {
     fn rows(
         &mut self,
         ui: &mut egui::Ui,
+        constant: &Arc<RwLock<GameConstant>>,
     ) {
         let text_height = egui::TextStyle::Body.resolve(ui.style()).size.max(ui.spacing().interact_size.y);
 
-        let available_height = ui.available_height();
-        let table = TableBuilder::new(ui)
+        // Leave the lower half for the player detail
+        let available_height = ui.available_height() / 2.0;
+        let mut table = TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
             .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
@@ -159,153 +387,421 @@ impl MainPanel {
             .column(Column::auto())
             .column(Column::auto())
             .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
             .column(Column::remainder())
             .max_scroll_height(available_height);
+        if let Some(row) = self.scroll_to.take().and_then(|index| self.visible.iter().position(|i| *i == index)) {
+            table = table.scroll_to_row(row, Some(egui::Align::Center));
+        }
 
+        let guard = constant.read();
+        let notes = self.notes.read();
         table
             .header(20.0, |mut header| {
+                for (key, text) in [
+                    (SortKey::Win, "Win"),
+                    (SortKey::StartTime, "Start Time"),
+                    (SortKey::Duration, "Duration"),
+                    (SortKey::GameMode, "Game Mode"),
+                    (SortKey::Hero, "Hero"),
+                    (SortKey::Kda, "K/D/A"),
+                    (SortKey::NetWorth, "Net Worth"),
+                ] {
+                    header.col(|ui| {
+                        let text = egui::RichText::new(self.filter.header_text(key, text)).strong();
+                        if ui.add(egui::Button::new(text).frame(false)).clicked() {
+                            self.filter.sort_by(key);
+                        }
+                    });
+                }
                 header.col(|ui| {
-                    ui.strong("Win");
-                });
-                header.col(|ui| {
-                    ui.strong("Start Time");
-                });
-                header.col(|ui| {
-                    ui.strong("Duration");
-                });
-                header.col(|ui| {
-                    ui.strong("Game Mode");
+                    ui.strong("Note");
                 });
                 header.col(|ui| {
                     ui.strong("Player Detail");
                 });
             })
             .body(|body| {
-                body.rows(text_height, 1, |mut row| {
-                    let row_index = row.index();
+                // `rows` only lays out the rows inside the viewport, so thousands of matches are fine
+                body.rows(text_height, self.visible.len(), |mut row| {
+                    let row_index = self.visible[row.index()];
+                    let view = &self.matches[row_index];
+                    row.set_selected(self.selected_index == Some(row_index));
+
+                    row.col(|ui| {
+                        ui.label(view.win_col());
+                    });
+
                     row.col(|ui| {
-                        ui.label(self.matches[row_index].win_col());
+                        ui.label(view.start_time_col());
                     });
 
                     row.col(|ui| {
-                        ui.label(self.matches[row_index].start_time_col());
+                        ui.label(view.duration_col());
                     });
 
                     row.col(|ui| {
-                        ui.label(self.matches[row_index].duration_col());
+                        ui.label(view.game_mode_col());
                     });
 
                     row.col(|ui| {
-                        ui.label(self.matches[row_index].game_mode_col());
+                        let hero_id = view.player_detail().hero_id;
+                        self.icons
+                            .show(ui, IconKey::hero(hero_id), &guard.hero(hero_id).to_string(), text_height, &guard);
+                    });
+
+                    row.col(|ui| {
+                        ui.label(view.kda_col());
+                    });
+
+                    row.col(|ui| {
+                        ui.label(view.player_detail().net_worth.to_string());
+                    });
+
+                    row.col(|ui| {
+                        if let Some(note) = notes.get(view.match_id()) {
+                            let response = ui.label(note.summary());
+                            if !note.text.is_empty() {
+                                response.on_hover_text(&note.text);
+                            }
+                        }
                     });
 
                     row.col(|ui| {
                         if ui.button("Click to see player detail").clicked() {
                             self.selected_index = Some(row_index);
+                            self.selected_player = None;
                         }
+                        open_in_menu(ui, |site| site.match_url(view.match_id()));
                     });
                 });
             });
     }
 
-    fn player_detail(
+    /// Archived matches without the tracked account, there is no "own" player to summarize
+    fn spectator_ui(
         &mut self,
         ui: &mut egui::Ui,
         constant: &Arc<RwLock<GameConstant>>,
     ) {
-        ui.heading("Player Detail");
-        ui.separator();
+        if self.spectated.is_empty() {
+            ui.label("No spectated matches, open one by ID");
+            return;
+        }
 
-        let guard = constant.read();
-        if let Some(index) = &self.selected_index {
-            let player = self.matches[*index].player_detail();
-            ui.group(|ui| {
-                let hero_name = id2name(player.hero_id, &guard.heroes_map);
-                ui.heading("Hero");
-                ui.horizontal(|ui| {
-                    ui.label(format!("英雄: {}", hero_name));
-                    ui.label(format!("命石: {}", player.hero_variant));
-                    ui.label(format!("等级: {}", player.level));
-                    ui.label(format!("正补: {}", player.last_hits));
-                    ui.label(format!("反补: {}", player.denies));
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size.max(ui.spacing().interact_size.y);
+        let available_height = ui.available_height() / 2.0;
+        TableBuilder::new(ui)
+            .id_salt("spectated")
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .columns(Column::auto(), 5)
+            .column(Column::remainder())
+            .max_scroll_height(available_height)
+            .header(20.0, |mut header| {
+                for text in ["Match ID", "Start Time", "Duration", "Game Mode", "Winner", "Score"] {
+                    header.col(|ui| {
+                        ui.strong(text);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(text_height, self.spectated.len(), |mut row| {
+                    let index = row.index();
+                    let detail = &self.spectated[index];
+                    row.set_selected(self.spectated_index == Some(index));
+                    row.col(|ui| {
+                        if ui.link(detail.match_id().to_string()).clicked() {
+                            self.spectated_index = Some(index);
+                            self.selected_player = None;
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(detail.start_time_col());
+                    });
+                    row.col(|ui| {
+                        ui.label(detail.duration_col());
+                    });
+                    row.col(|ui| {
+                        ui.label(detail.game_mode_col());
+                    });
+                    row.col(|ui| {
+                        if detail.radiant_win() {
+                            ui.colored_label(RADIANT_COLOR, "天辉");
+                        } else {
+                            ui.colored_label(DIRE_COLOR, "夜魇");
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(detail.score_col());
+                        open_in_menu(ui, |site| site.match_url(detail.match_id()));
+                    });
                 });
             });
 
-            ui.add_space(10.0);
+        ui.add_space(30.0);
 
-            ui.group(|ui| {
-                ui.heading("装备");
-                ui.horizontal_wrapped(|ui| {
-                    let item_0 = id2name(player.item_0, &guard.items_map);
-                    let item_1 = id2name(player.item_1, &guard.items_map);
-                    let item_2 = id2name(player.item_2, &guard.items_map);
-                    let item_3 = id2name(player.item_3, &guard.items_map);
-                    let item_4 = id2name(player.item_4, &guard.items_map);
-                    let item_5 = id2name(player.item_5, &guard.items_map);
-                    let backpack_0 = id2name(player.backpack_0, &guard.items_map);
-                    let backpack_1 = id2name(player.backpack_1, &guard.items_map);
-                    let backpack_2 = id2name(player.backpack_2, &guard.items_map);
-                    let item_neutral = id2name(player.item_neutral, &guard.items_map);
-                    let moonshard = if player.moonshard == 1 { "是" } else { "否" };
-                    let aghanims_scepter = if player.aghanims_scepter == 1 { "是" } else { "否" };
-                    let aghanims_shard = if player.aghanims_shard == 1 { "是" } else { "否" };
-                    ui.label("物品栏");
-                    ui.label(item_0);
-                    ui.label(item_1);
-                    ui.label(item_2);
-                    ui.label(item_3);
-                    ui.label(item_4);
-                    ui.label(item_5);
-                    ui.label("背包");
-                    ui.label(backpack_0);
-                    ui.label(backpack_1);
-                    ui.label(backpack_2);
-                    ui.label("中立物品");
-                    ui.label(item_neutral);
-
-                    ui.label(format!("A杖: {}", aghanims_scepter));
-                    ui.label(format!("魔晶: {}", aghanims_shard));
-                    ui.label(format!("银月: {}", moonshard));
-                });
+        if let Some(index) = self.spectated_index {
+            ui.horizontal(|ui| {
+                ui.heading("Match Detail");
+                if ui.button("对比").clicked() {
+                    let player_index = self.selected_player.unwrap_or_default();
+                    self.trigger_compare_match(self.spectated[index].match_id(), player_index);
+                }
             });
+            ui.separator();
+            let guard = constant.read();
+            let detail = &self.spectated[index];
+            note_editor(ui, detail.match_id(), &mut self.notes.write());
+            ui.add_space(10.0);
+            match_detail(ui, &mut self.icons, &mut self.selected_player, detail, 0, &guard);
+        }
+    }
+
+    fn player_detail(
+        &mut self,
+        ui: &mut egui::Ui,
+        constant: &Arc<RwLock<GameConstant>>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.heading("Player Detail");
+            if let Some(index) = self.selected_index {
+                if ui.button("对比").clicked() {
+                    let view = &self.matches[index];
+                    let player_index = self.selected_player.unwrap_or(view.player_index());
+                    self.trigger_compare_match(view.match_id(), player_index);
+                }
+            }
+        });
+        ui.separator();
 
+        let guard = constant.read();
+        if let Some(index) = self.selected_index {
+            let view = &self.matches[index];
+            note_editor(ui, view.match_id(), &mut self.notes.write());
             ui.add_space(10.0);
+            match_detail(
+                ui,
+                &mut self.icons,
+                &mut self.selected_player,
+                view.match_detail(),
+                view.player_index(),
+                &guard,
+            );
+        }
+    }
+}
 
-            ui.group(|ui| {
-                ui.heading("数据");
-                ui.horizontal(|ui| {
-                    ui.label(format!("击杀: {}", player.kills));
-                    ui.label(format!("死亡: {}", player.deaths));
-                    ui.label(format!("助攻: {}", player.assists));
-                    ui.label(format!("GPM: {}", player.gold_per_min));
-                    ui.label(format!("XPM: {}", player.xp_per_min));
-                    ui.label(format!("总经济: {}", player.gold));
-                    ui.label(format!("伤害: {}", player.hero_damage));
-                    // 承伤
-                    ui.label(format!("治疗: {}", player.hero_healing));
-                    ui.label(format!("建筑: {}", player.tower_damage));
-
-                    ui.label(format!("经济输出比: {}", (player.gold / player.hero_damage) as f32 / 100.0));
-                    ui.label(format!("参战率: {}", self.matches[*index].participation_rate()));
-                });
-            });
+/// Every player of `detail`, `default_player` is shown until another one is picked
+fn match_detail(
+    ui: &mut egui::Ui,
+    icons: &mut IconCache,
+    selected_player: &mut Option<usize>,
+    detail: &MatchDetail,
+    default_player: usize,
+    constant: &GameConstant,
+) {
+    let players = detail.players();
+    // A pick past the end, e.g. from a 10 player match while this one is 1v1, falls back too
+    let player_index = selected_player.filter(|index| *index < players.len()).unwrap_or(default_player);
+    ui.horizontal_wrapped(|ui| {
+        for (i, p) in players.iter().enumerate() {
+            let color = if p.is_radiant() { RADIANT_COLOR } else { DIRE_COLOR };
+            let hero_name = constant.hero(p.hero_id).to_string();
+            let response = match icons.image(ui, IconKey::hero(p.hero_id), 24.0, constant) {
+                Some(image) => ui.add(egui::ImageButton::new(image).selected(i == player_index)).on_hover_text(hero_name),
+                None => ui.selectable_label(i == player_index, egui::RichText::new(hero_name).color(color)),
+            };
+            if response.clicked() {
+                *selected_player = Some(i);
+            }
+        }
+    });
+
+    let Some(player) = players.get(player_index) else {
+        return;
+    };
+    ui.group(|ui| {
+        let hero_name = constant.hero(player.hero_id).to_string();
+        ui.heading("Hero");
+        ui.horizontal(|ui| {
+            icons.show(ui, IconKey::hero(player.hero_id), &hero_name, 32.0, constant);
+            ui.label(format!("英雄: {}", hero_name));
+            let response = ui.label(format!("命石: {}", constant.facet_name(player.hero_id, player.hero_variant)));
+            if let Some(facet) = constant.facet(player.hero_id, player.hero_variant) {
+                response.on_hover_text(&facet.description);
+            }
+            ui.label(format!("等级: {}", player.level));
+            ui.label(format!("正补: {}", player.last_hits));
+            ui.label(format!("反补: {}", player.denies));
+            if !player.is_anonymous() {
+                open_in_menu(ui, |site| site.player_url(player.account_id));
+            }
+        });
+    });
+
+    ui.add_space(10.0);
+
+    ui.group(|ui| {
+        ui.heading("装备");
+        item_slots(ui, icons, &player.items(), constant);
+        ui.horizontal(|ui| {
+            let moonshard = if player.moonshard == 1 { "是" } else { "否" };
+            let aghanims_scepter = if player.aghanims_scepter == 1 { "是" } else { "否" };
+            let aghanims_shard = if player.aghanims_shard == 1 { "是" } else { "否" };
+            ui.label(format!("A杖: {}", aghanims_scepter));
+            ui.label(format!("魔晶: {}", aghanims_shard));
+            ui.label(format!("银月: {}", moonshard));
+        });
+
+        for unit in &player.additional_units {
+            ui.separator();
+            ui.strong(unit_name(&unit.unitname));
+            item_slots(ui, icons, &unit.items(), constant);
+        }
+    });
+
+    ui.add_space(10.0);
+
+    ui.group(|ui| {
+        ui.heading("数据");
+        ui.horizontal(|ui| {
+            ui.label(format!("击杀: {}", player.kills));
+            ui.label(format!("死亡: {}", player.deaths));
+            ui.label(format!("助攻: {}", player.assists));
+            ui.label(format!("GPM: {}", player.gold_per_min));
+            ui.label(format!("XPM: {}", player.xp_per_min));
+            ui.label(format!("总经济: {}", player.gold));
+            ui.label(format!("伤害: {}", player.hero_damage));
+            // 承伤
+            ui.label(format!("治疗: {}", player.hero_healing));
+            ui.label(format!("建筑: {}", player.tower_damage));
+        });
+        let metrics = detail.player_metrics(player);
+        ui.horizontal(|ui| {
+            ui.label(format!("KDA: {:.2}", metrics.kda));
+            ui.label(format!("参战率: {:.0}%", metrics.kill_participation * 100.0));
+            ui.label(format!("每金伤害: {:.2}", metrics.damage_per_gold));
+            ui.label(format!("分均补刀: {:.1}", metrics.cs_per_min));
+            ui.label(format!("经济占比: {:.0}%", metrics.net_worth_share * 100.0));
+            ui.label(format!("伤害占比: {:.0}%", metrics.damage_share * 100.0));
+        });
+    });
+
+    ui.add_space(10.0);
+
+    ui.group(|ui| {
+        ui.heading("加点");
+        skill_build(ui, icons, &player.ability_upgrades, constant);
+    });
+
+    ui.add_space(10.0);
+
+    ui.group(|ui| {
+        ui.heading("建筑");
+        match detail.buildings() {
+            Some((radiant, dire)) => building_map(ui, &radiant, &dire),
+            None => {
+                ui.label("No building data for this match");
+            }
         }
+    });
+
+    let picks_bans = detail.picks_bans();
+    if !picks_bans.is_empty() {
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("BP");
+            draft_timeline(ui, &picks_bans, constant);
+        });
     }
 }
 
-pub struct Menu {
-    latest_matches: bool,
-    friends: bool,
+/// Inventory, backpack, neutral and enchantment slot as returned by `PlayerDetail::items`
+fn item_slots(
+    ui: &mut egui::Ui,
+    icons: &mut IconCache,
+    items: &[i32],
+    constant: &GameConstant,
+) {
+    ui.horizontal_wrapped(|ui| {
+        for (slot, &id) in items.iter().enumerate() {
+            let group = match slot {
+                0 => Some("物品栏"),
+                6 => Some("背包"),
+                9 => Some("中立物品"),
+                // Older matches have no enchantment slot
+                10 if id == 0 => continue,
+                10 => Some("附魔"),
+                _ => None,
+            };
+            if let Some(group) = group {
+                ui.label(group);
+            }
+            icons.show(ui, IconKey::item(id), &constant.item(id).to_string(), 24.0, constant);
+            if slot < 9 {
+                continue;
+            }
+            if let Some(tier) = constant.neutral_tier(id) {
+                tier_badge(ui, tier);
+            }
+        }
+    });
 }
 
-impl Menu {
-    fn init() -> Self {
-        Self {
-            latest_matches: true,
-            friends: false,
+/// `T1` to `T5`, colored like the neutral item tiers in game
+fn tier_badge(
+    ui: &mut egui::Ui,
+    tier: u8,
+) {
+    let color = match tier {
+        1 => egui::Color32::from_rgb(190, 190, 190),
+        2 => egui::Color32::from_rgb(92, 184, 92),
+        3 => egui::Color32::from_rgb(66, 139, 202),
+        4 => egui::Color32::from_rgb(155, 89, 182),
+        _ => egui::Color32::from_rgb(230, 126, 34),
+    };
+    ui.label(
+        egui::RichText::new(format!("T{}", tier))
+            .small()
+            .strong()
+            .color(egui::Color32::BLACK)
+            .background_color(color),
+    )
+    .on_hover_text(format!("{} 级中立物品", tier));
+}
+
+/// "Open in…" menu with a link to every external site
+fn open_in_menu(
+    ui: &mut egui::Ui,
+    url: impl Fn(ExternalSite) -> String,
+) {
+    ui.menu_button("Open in…", |ui| {
+        for site in ExternalSite::ALL {
+            ui.hyperlink_to(site.name(), url(site));
         }
+    });
+}
+
+/// Display name of an `AdditionalUnit::unitname`
+fn unit_name(unitname: &str) -> String {
+    match unitname {
+        "spirit_bear" => String::from("熊灵"),
+        other => other.to_owned(),
     }
+}
+
+pub struct Menu {
+    view: View,
+    overlay_open: bool,
+}
 
+impl Menu {
     fn show_menu(
         &mut self,
         ui: &mut egui::Ui,
@@ -314,13 +810,14 @@ impl Menu {
             ui.heading("Menu");
             ui.separator();
 
-            if ui.toggle_value(&mut self.latest_matches, "Latest Matches").clicked() {
-                self.friends = false;
-            }
-
-            if ui.toggle_value(&mut self.friends, "Friends").clicked() {
-                self.latest_matches = false;
-            }
+            ui.selectable_value(&mut self.view, View::LatestMatches, "Latest Matches");
+            ui.selectable_value(&mut self.view, View::Today, "Today");
+            ui.selectable_value(&mut self.view, View::Goals, "Goals");
+            ui.selectable_value(&mut self.view, View::Compare, "Compare");
+            ui.selectable_value(&mut self.view, View::Friends, "Friends");
+            ui.selectable_value(&mut self.view, View::Settings, "Settings");
+            ui.separator();
+            ui.toggle_value(&mut self.overlay_open, "Overlay");
         });
     }
 }
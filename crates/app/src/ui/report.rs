@@ -0,0 +1,254 @@
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use common::{
+    report::{self, Report},
+    streak::Streaks,
+};
+use eframe::egui;
+use egui::mutex::RwLock;
+use egui_extras::DatePickerButton;
+use tracing::{error, info};
+
+use super::{Component, DIRE_COLOR, RADIANT_COLOR};
+use crate::{archive::MatchArchive, constant::GameConstant, notes::NoteBook, persist::write_atomic, state::AppState};
+
+/// Exported reports are written here, named after the day
+pub const REPORT_DIR: &str = "config/reports";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+/// Daily report plus one report per play session of the picked day
+pub struct TodayPanel {
+    archive: Arc<RwLock<MatchArchive>>,
+    notes: Arc<RwLock<NoteBook>>,
+    date: NaiveDate,
+    /// Archive generation, account and date behind `day` and `sessions`
+    built_from: Option<(u64, i64, NaiveDate)>,
+    day: Option<Report>,
+    /// Match id, hero id and start time of the picked day's games, newest first
+    games: Vec<(i64, i32, i64)>,
+    /// Newest first
+    sessions: Vec<Report>,
+    /// Over every archived match, not only the picked day
+    streaks: Streaks,
+    /// Result of the last export, shown under the buttons
+    export_status: Option<String>,
+}
+
+impl TodayPanel {
+    pub fn new(
+        archive: Arc<RwLock<MatchArchive>>,
+        notes: Arc<RwLock<NoteBook>>,
+    ) -> Self {
+        Self {
+            archive,
+            notes,
+            date: chrono::Local::now().date_naive(),
+            built_from: None,
+            day: None,
+            games: Vec::new(),
+            sessions: Vec::new(),
+            streaks: Streaks::default(),
+            export_status: None,
+        }
+    }
+
+    fn rebuild(
+        &mut self,
+        account_id: i64,
+    ) {
+        let archive = self.archive.read();
+        let key = (archive.generation(), account_id, self.date);
+        if self.built_from == Some(key) {
+            return;
+        }
+
+        let views = archive.views(account_id);
+        let played = common::stats::played_on(views.iter(), self.date).collect::<Vec<_>>();
+        self.day = Report::new(&played);
+        self.games = played
+            .iter()
+            .map(|view| (view.match_id(), view.player_detail().hero_id, view.start_time()))
+            .collect();
+        self.sessions = report::sessions(played);
+        self.streaks = Streaks::new(views.iter());
+        self.built_from = Some(key);
+    }
+
+    fn export(
+        &mut self,
+        constant: &GameConstant,
+        format: ExportFormat,
+    ) {
+        let Some(day) = &self.day else {
+            return;
+        };
+        let title = format!("Dota 2 日报 {}", self.date);
+        let hero_name = |id| constant.hero(id).to_string();
+        let facet_name = |id, variant| constant.facet_name(id, variant).to_string();
+        let notes = self.notes.read();
+        let notes = self
+            .games
+            .iter()
+            .filter_map(|(match_id, hero_id, start_time)| {
+                let note = notes.get(*match_id)?;
+                let parts = [
+                    report::clock_col(*start_time),
+                    hero_name(*hero_id),
+                    format!("#{}", match_id),
+                    note.summary(),
+                    note.text.replace('\n', " "),
+                ];
+                Some(parts.into_iter().filter(|part| !part.is_empty()).collect::<Vec<_>>().join(" "))
+            })
+            .collect::<Vec<_>>();
+        let contents = match format {
+            ExportFormat::Markdown => day.to_markdown(&title, hero_name, facet_name, &self.sessions, &notes),
+            ExportFormat::Html => day.to_html(&title, hero_name, facet_name, &self.sessions, &notes),
+        };
+        let path = format!("{}/{}.{}", REPORT_DIR, self.date, format.extension());
+        self.export_status = Some(match write_atomic(&path, &contents, false) {
+            Ok(_) => {
+                info!("Exported report to {}", path);
+                format!("已导出到 {}", path)
+            }
+            Err(e) => {
+                error!("Failed to export report: {}", e);
+                format!("导出失败: {}", e)
+            }
+        });
+    }
+}
+
+impl Component for TodayPanel {
+    fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut AppState,
+        constant: &Arc<RwLock<GameConstant>>,
+    ) {
+        self.rebuild(state.account_id);
+        let constant = constant.read();
+        let mut export = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("今日");
+                ui.add(DatePickerButton::new(&mut self.date).id_salt("report_date"));
+                if ui.button("Today").clicked() {
+                    self.date = chrono::Local::now().date_naive();
+                }
+            });
+            streaks_ui(ui, &self.streaks);
+            ui.separator();
+
+            let Some(day) = &self.day else {
+                ui.label("当天没有比赛");
+                return;
+            };
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                report_ui(ui, "day", day, &constant);
+                ui.horizontal(|ui| {
+                    if ui.button("导出 Markdown").clicked() {
+                        export = Some(ExportFormat::Markdown);
+                    }
+                    if ui.button("导出 HTML").clicked() {
+                        export = Some(ExportFormat::Html);
+                    }
+                });
+                if let Some(status) = &self.export_status {
+                    ui.weak(status);
+                }
+
+                for (index, session) in self.sessions.iter().enumerate() {
+                    ui.separator();
+                    ui.strong(session.session_title(self.sessions.len() - index));
+                    report_ui(ui, &format!("session_{}", index), session, &constant);
+                }
+            });
+        });
+        if let Some(format) = export {
+            self.export(&constant, format);
+        }
+    }
+}
+
+fn report_ui(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    report: &Report,
+    constant: &GameConstant,
+) {
+    let hero_name = |id| constant.hero(id).to_string();
+    egui::Grid::new(format!("{}_summary", id_salt)).num_columns(2).show(ui, |ui| {
+        for (label, value) in report.summary(&hero_name) {
+            ui.label(label);
+            ui.label(value);
+            ui.end_row();
+        }
+    });
+    ui.add_space(5.0);
+    egui::Grid::new(format!("{}_heroes", id_salt))
+        .num_columns(6)
+        .striped(true)
+        .show(ui, |ui| {
+            for header in ["英雄", "场次", "胜场", "胜率", "KDA", "命石"] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for hero in &report.heroes {
+                ui.label(hero_name(hero.hero_id));
+                ui.label(hero.games.to_string());
+                ui.label(hero.wins.to_string());
+                ui.label(format!("{:.0}%", hero.win_rate() * 100.0));
+                ui.label(format!("{:.2}", hero.kda()));
+                ui.horizontal(|ui| {
+                    for (variant, record) in &hero.facets {
+                        let response = ui.label(format!(
+                            "{} {} ({:.0}%)",
+                            constant.facet_name(hero.hero_id, *variant),
+                            record,
+                            record.win_rate() * 100.0
+                        ));
+                        if let Some(facet) = constant.facet(hero.hero_id, *variant) {
+                            response.on_hover_text(&facet.description);
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+}
+
+fn streaks_ui(
+    ui: &mut egui::Ui,
+    streaks: &Streaks,
+) {
+    ui.horizontal(|ui| {
+        if let Some(current) = streaks.current {
+            let (label, color) = if current.win {
+                ("连胜", RADIANT_COLOR)
+            } else {
+                ("连败", DIRE_COLOR)
+            };
+            ui.colored_label(color, format!("当前{} {}", label, current.length));
+            ui.separator();
+        }
+        ui.label(format!("最长连胜 {}", streaks.longest_win));
+        ui.separator();
+        ui.label(format!("最长连败 {}", streaks.longest_loss));
+    });
+}
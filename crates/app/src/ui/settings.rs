@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use common::data::constant::ConstantLanguage;
+use eframe::egui;
+use egui::{mutex::RwLock, FontData, FontDefinitions, FontFamily, ThemePreference};
+use tracing::error;
+
+use super::Component;
+use crate::{
+    constant::GameConstant,
+    state::{AppState, DataSource},
+};
+
+/// Bundled LXGW WenKai plus the font file picked in the settings, if it can be read
+pub fn font_definitions(font_path: Option<&str>) -> FontDefinitions {
+    // Get font from: https://github.com/lxgw/LxgwWenKai
+    let mut fonts = FontDefinitions::default();
+    fonts.font_data.insert(
+        "kx_font".to_owned(),
+        Arc::new(FontData::from_static(include_bytes!("../../../../assets/LXGWWenKai-Regular.ttf"))),
+    );
+    let proportional = fonts.families.get_mut(&FontFamily::Proportional).unwrap();
+    proportional.push("kx_font".to_owned());
+
+    if let Some(path) = font_path {
+        match std::fs::read(path) {
+            Ok(bytes) => {
+                fonts.font_data.insert("custom_font".to_owned(), Arc::new(FontData::from_owned(bytes)));
+                // WenKai stays as the fallback for glyphs the custom font lacks
+                proportional.insert(0, "custom_font".to_owned());
+            }
+            Err(e) => {
+                error!("Failed to read font {}: {}", path, e);
+            }
+        }
+    }
+    fonts
+}
+
+pub struct SettingsPanel {
+    /// Only written to the settings on "Apply", fonts are not reloaded on every keystroke
+    font_path: String,
+}
+
+impl SettingsPanel {
+    pub fn new(font_path: Option<&str>) -> Self {
+        Self {
+            font_path: font_path.unwrap_or_default().to_owned(),
+        }
+    }
+}
+
+impl Component for SettingsPanel {
+    fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut AppState,
+        _constant: &Arc<RwLock<GameConstant>>,
+    ) {
+        let settings = &mut state.settings;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("设置");
+            ui.separator();
+
+            egui::Grid::new("settings").num_columns(2).spacing([20.0, 10.0]).show(ui, |ui| {
+                ui.label("主题");
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut settings.theme, ThemePreference::System, "跟随系统");
+                    ui.selectable_value(&mut settings.theme, ThemePreference::Dark, "深色");
+                    ui.selectable_value(&mut settings.theme, ThemePreference::Light, "浅色");
+                });
+                ui.end_row();
+
+                ui.label("界面缩放");
+                ui.add(egui::Slider::new(&mut settings.ui_scale, 0.5..=2.0).step_by(0.1));
+                ui.end_row();
+
+                ui.label("字体");
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        if ui.radio(settings.font_path.is_none(), "内置 (LXGW WenKai)").clicked() {
+                            settings.font_path = None;
+                        }
+                        let system_font = ui.radio(settings.font_path.is_some(), "系统字体文件");
+                        if system_font.clicked() && !self.font_path.is_empty() {
+                            settings.font_path = Some(self.font_path.clone());
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.font_path).hint_text("C:/Windows/Fonts/msyh.ttc"));
+                        if ui.add_enabled(!self.font_path.is_empty(), egui::Button::new("Apply")).clicked() {
+                            settings.font_path = Some(self.font_path.clone());
+                        }
+                    });
+                });
+                ui.end_row();
+
+                ui.label("自动刷新");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut settings.refresh_interval).range(0..=120).suffix(" min"));
+                    if settings.refresh_interval == 0 {
+                        ui.weak("关闭");
+                    }
+                });
+                ui.end_row();
+
+                ui.label("数据源");
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut settings.data_source, DataSource::Steam, "Steam API");
+                        ui.selectable_value(&mut settings.data_source, DataSource::Mirror, "镜像");
+                    });
+                    if settings.data_source == DataSource::Mirror {
+                        ui.add(egui::TextEdit::singleline(&mut settings.mirror_url).hint_text("https://steam-api.example.com"));
+                    }
+                });
+                ui.end_row();
+
+                ui.label("本地 API");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut settings.api_enabled, "启用");
+                    ui.add(egui::DragValue::new(&mut settings.api_port).range(1024..=65535));
+                    if settings.api_enabled {
+                        ui.hyperlink(format!("http://127.0.0.1:{}/matches", settings.api_port));
+                    }
+                });
+                ui.end_row();
+
+                ui.label("直播浮窗");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut settings.overlay_results).range(1..=30).suffix(" 场"));
+                    if settings.api_enabled {
+                        ui.hyperlink(format!(
+                            "http://127.0.0.1:{}/overlay?results={}",
+                            settings.api_port, settings.overlay_results
+                        ));
+                    }
+                });
+                ui.end_row();
+
+                ui.label("休息提醒");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut settings.break_reminder, "启用");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.tilt_threshold)
+                            .range(2..=10)
+                            .prefix("连败 ")
+                            .suffix(" 场"),
+                    );
+                });
+                ui.end_row();
+
+                ui.label("常量语言");
+                egui::ComboBox::from_id_salt("settings_language")
+                    .selected_text(settings.language.label())
+                    .show_ui(ui, |ui| {
+                        for language in ConstantLanguage::ALL {
+                            ui.selectable_value(&mut settings.language, language, language.label());
+                        }
+                    });
+                ui.end_row();
+            });
+        });
+    }
+}
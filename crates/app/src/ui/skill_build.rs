@@ -0,0 +1,45 @@
+use common::data::matches::AbilityUpgrade;
+use eframe::egui;
+use egui::{Color32, RichText};
+
+use super::icon::{IconCache, IconKey};
+use crate::constant::GameConstant;
+
+const TALENT_COLOR: Color32 = Color32::from_rgb(230, 180, 60);
+
+/// Level-by-level skill build, talents are highlighted
+pub fn skill_build(
+    ui: &mut egui::Ui,
+    icons: &mut IconCache,
+    upgrades: &[AbilityUpgrade],
+    constant: &GameConstant,
+) {
+    if upgrades.is_empty() {
+        ui.label("No skill build for this player");
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for upgrade in upgrades {
+            let name = constant.ability(upgrade.ability).to_string();
+            let is_talent = constant.is_talent(upgrade.ability);
+
+            let text = if is_talent {
+                RichText::new(format!("天赋: {}", name)).color(TALENT_COLOR)
+            } else {
+                RichText::new(name)
+            };
+            egui::Frame::group(ui.style()).inner_margin(4.0).show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.weak(format!("Lv{}", upgrade.level));
+                    if !is_talent {
+                        if let Some(image) = icons.image(ui, IconKey::ability(upgrade.ability), 32.0, constant) {
+                            ui.add(image);
+                        }
+                    }
+                    ui.label(text).on_hover_text(format!("{}m{}s", upgrade.time / 60, upgrade.time % 60));
+                });
+            });
+        }
+    });
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use super::filter::SortKey;
+
+/// Key of [`UiState`] in eframe's storage
+pub const UI_STATE_KEY: &str = "ui_state";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum View {
+    #[default]
+    LatestMatches,
+    Today,
+    Goals,
+    Compare,
+    Friends,
+    Settings,
+}
+
+/// What the UI looked like on exit, restored on the next start. The window, panel widths and
+/// table column widths are kept by eframe and egui themselves, the theme is in `Settings`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub view: View,
+    pub sort_key: SortKey,
+    pub descending: bool,
+    pub selected_match_id: Option<i64>,
+    pub overlay_open: bool,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            view: View::default(),
+            sort_key: SortKey::StartTime,
+            descending: true,
+            selected_match_id: None,
+            overlay_open: false,
+        }
+    }
+}
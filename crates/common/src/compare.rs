@@ -0,0 +1,131 @@
+//! Stat by stat comparison of two players, e.g. lane opponents or the same hero in two games
+
+use std::cmp::Ordering;
+
+use crate::data::matches::PlayerDetail;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparedStat {
+    Kills,
+    Deaths,
+    Assists,
+    GoldPerMin,
+    XpPerMin,
+    HeroDamage,
+    HeroHealing,
+    TowerDamage,
+    LastHits,
+    Denies,
+    NetWorth,
+}
+
+impl ComparedStat {
+    pub const ALL: [ComparedStat; 11] = [
+        ComparedStat::Kills,
+        ComparedStat::Deaths,
+        ComparedStat::Assists,
+        ComparedStat::GoldPerMin,
+        ComparedStat::XpPerMin,
+        ComparedStat::HeroDamage,
+        ComparedStat::HeroHealing,
+        ComparedStat::TowerDamage,
+        ComparedStat::LastHits,
+        ComparedStat::Denies,
+        ComparedStat::NetWorth,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComparedStat::Kills => "击杀",
+            ComparedStat::Deaths => "死亡",
+            ComparedStat::Assists => "助攻",
+            ComparedStat::GoldPerMin => "GPM",
+            ComparedStat::XpPerMin => "XPM",
+            ComparedStat::HeroDamage => "伤害",
+            ComparedStat::HeroHealing => "治疗",
+            ComparedStat::TowerDamage => "建筑",
+            ComparedStat::LastHits => "正补",
+            ComparedStat::Denies => "反补",
+            ComparedStat::NetWorth => "净资产",
+        }
+    }
+
+    pub fn value(
+        &self,
+        player: &PlayerDetail,
+    ) -> i32 {
+        match self {
+            ComparedStat::Kills => player.kills,
+            ComparedStat::Deaths => player.deaths,
+            ComparedStat::Assists => player.assists,
+            ComparedStat::GoldPerMin => player.gold_per_min,
+            ComparedStat::XpPerMin => player.xp_per_min,
+            ComparedStat::HeroDamage => player.hero_damage,
+            ComparedStat::HeroHealing => player.hero_healing,
+            ComparedStat::TowerDamage => player.tower_damage,
+            ComparedStat::LastHits => player.last_hits,
+            ComparedStat::Denies => player.denies,
+            ComparedStat::NetWorth => player.net_worth,
+        }
+    }
+
+    /// Only deaths are better when lower
+    fn higher_is_better(&self) -> bool {
+        !matches!(self, ComparedStat::Deaths)
+    }
+}
+
+/// One row of the comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatDelta {
+    pub stat: ComparedStat,
+    pub left: i32,
+    pub right: i32,
+}
+
+impl StatDelta {
+    /// `left - right`
+    pub fn delta(&self) -> i32 {
+        self.left - self.right
+    }
+
+    /// `Greater` when the left player did better on this stat
+    pub fn left_is(&self) -> Ordering {
+        let ordering = self.left.cmp(&self.right);
+        if self.stat.higher_is_better() {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    }
+}
+
+/// Every `ComparedStat` of `left` against `right`
+pub fn compare(
+    left: &PlayerDetail,
+    right: &PlayerDetail,
+) -> Vec<StatDelta> {
+    ComparedStat::ALL
+        .iter()
+        .map(|stat| StatDelta {
+            stat: *stat,
+            left: stat.value(left),
+            right: stat.value(right),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fewer_deaths_is_better() {
+        let delta = |stat, left, right| StatDelta { stat, left, right };
+
+        assert_eq!(delta(ComparedStat::Kills, 10, 5).left_is(), Ordering::Greater);
+        assert_eq!(delta(ComparedStat::Deaths, 10, 5).left_is(), Ordering::Less);
+        assert_eq!(delta(ComparedStat::Deaths, 10, 5).delta(), 5);
+        assert_eq!(delta(ComparedStat::GoldPerMin, 500, 500).left_is(), Ordering::Equal);
+    }
+}
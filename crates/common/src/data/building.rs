@@ -0,0 +1,172 @@
+//! Decoders for the `tower_status_*` and `barracks_status_*` bitmasks of a match detail, a set bit
+//! means the building is still standing when the game ends.
+//!
+//! Reference: https://github.com/SteamDatabase/SteamTracking (CMsgDOTAMatch)
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    Top,
+    Mid,
+    Bot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tower {
+    pub lane: Lane,
+    /// 1 to 4, tier 4 towers guard the ancient and only exist for `Top` and `Bot`
+    pub tier: u8,
+}
+
+impl Tower {
+    /// In bit order of the tower bitmask
+    pub const ALL: [Tower; 11] = [
+        Tower { lane: Lane::Top, tier: 1 },
+        Tower { lane: Lane::Top, tier: 2 },
+        Tower { lane: Lane::Top, tier: 3 },
+        Tower { lane: Lane::Mid, tier: 1 },
+        Tower { lane: Lane::Mid, tier: 2 },
+        Tower { lane: Lane::Mid, tier: 3 },
+        Tower { lane: Lane::Bot, tier: 1 },
+        Tower { lane: Lane::Bot, tier: 2 },
+        Tower { lane: Lane::Bot, tier: 3 },
+        Tower { lane: Lane::Top, tier: 4 },
+        Tower { lane: Lane::Bot, tier: 4 },
+    ];
+
+    pub fn name(&self) -> String {
+        format!("{:?} T{}", self.lane, self.tier)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarracksKind {
+    Melee,
+    Ranged,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Barracks {
+    pub lane: Lane,
+    pub kind: BarracksKind,
+}
+
+impl Barracks {
+    /// In bit order of the barracks bitmask
+    pub const ALL: [Barracks; 6] = [
+        Barracks {
+            lane: Lane::Top,
+            kind: BarracksKind::Melee,
+        },
+        Barracks {
+            lane: Lane::Top,
+            kind: BarracksKind::Ranged,
+        },
+        Barracks {
+            lane: Lane::Mid,
+            kind: BarracksKind::Melee,
+        },
+        Barracks {
+            lane: Lane::Mid,
+            kind: BarracksKind::Ranged,
+        },
+        Barracks {
+            lane: Lane::Bot,
+            kind: BarracksKind::Melee,
+        },
+        Barracks {
+            lane: Lane::Bot,
+            kind: BarracksKind::Ranged,
+        },
+    ];
+
+    pub fn name(&self) -> String {
+        format!("{:?} {:?}", self.lane, self.kind)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct TowerStatus(i32);
+
+impl TowerStatus {
+    pub fn is_standing(
+        &self,
+        tower: Tower,
+    ) -> bool {
+        Tower::ALL.iter().position(|t| *t == tower).is_some_and(|bit| self.0 & (1 << bit) != 0)
+    }
+
+    /// Every tower of one side together with whether it is still standing
+    pub fn towers(&self) -> impl Iterator<Item = (Tower, bool)> + '_ {
+        Tower::ALL.into_iter().map(|tower| (tower, self.is_standing(tower)))
+    }
+
+    pub fn standing(&self) -> usize {
+        self.towers().filter(|(_, standing)| *standing).count()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct BarracksStatus(i32);
+
+impl BarracksStatus {
+    pub fn is_standing(
+        &self,
+        barracks: Barracks,
+    ) -> bool {
+        Barracks::ALL
+            .iter()
+            .position(|b| *b == barracks)
+            .is_some_and(|bit| self.0 & (1 << bit) != 0)
+    }
+
+    /// Every barracks of one side together with whether it is still standing
+    pub fn barracks(&self) -> impl Iterator<Item = (Barracks, bool)> + '_ {
+        Barracks::ALL.into_iter().map(|barracks| (barracks, self.is_standing(barracks)))
+    }
+
+    pub fn standing(&self) -> usize {
+        self.barracks().filter(|(_, standing)| *standing).count()
+    }
+}
+
+/// Buildings of one side at the end of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TeamBuildings {
+    pub towers: TowerStatus,
+    pub barracks: BarracksStatus,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_standing() {
+        let towers = TowerStatus(0b111_1111_1111);
+        let barracks = BarracksStatus(0b11_1111);
+        assert_eq!(towers.standing(), 11);
+        assert_eq!(barracks.standing(), 6);
+    }
+
+    #[test]
+    fn decode_bits() {
+        // Top T1 (bit 0) and Mid T1 (bit 3) destroyed
+        let towers = TowerStatus(0b111_1111_0110);
+        assert!(!towers.is_standing(Tower { lane: Lane::Top, tier: 1 }));
+        assert!(towers.is_standing(Tower { lane: Lane::Top, tier: 2 }));
+        assert!(!towers.is_standing(Tower { lane: Lane::Mid, tier: 1 }));
+        assert!(towers.is_standing(Tower { lane: Lane::Bot, tier: 4 }));
+
+        // Only bot ranged (bit 5) left
+        let barracks = BarracksStatus(0b10_0000);
+        assert!(barracks.is_standing(Barracks {
+            lane: Lane::Bot,
+            kind: BarracksKind::Ranged
+        }));
+        assert_eq!(barracks.standing(), 1);
+    }
+}
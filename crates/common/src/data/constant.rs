@@ -2,35 +2,105 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+const VALVE_CDN: &str = "https://cdn.cloudflare.steamstatic.com/apps/dota2/images/dota_react";
+
 #[derive(Serialize)]
 pub struct ConstantRequest {
     query: String,
 }
 
+/// Language of the display names in the constants
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConstantLanguage {
+    #[default]
+    SChinese,
+    TChinese,
+    English,
+    Russian,
+}
+
+impl ConstantLanguage {
+    pub const ALL: [ConstantLanguage; 4] = [
+        ConstantLanguage::SChinese,
+        ConstantLanguage::TChinese,
+        ConstantLanguage::English,
+        ConstantLanguage::Russian,
+    ];
+
+    /// Value of the Stratz `Language` enum
+    fn graphql_name(&self) -> &'static str {
+        match self {
+            ConstantLanguage::SChinese => "S_CHINESE",
+            ConstantLanguage::TChinese => "T_CHINESE",
+            ConstantLanguage::English => "ENGLISH",
+            ConstantLanguage::Russian => "RUSSIAN",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConstantLanguage::SChinese => "简体中文",
+            ConstantLanguage::TChinese => "繁體中文",
+            ConstantLanguage::English => "English",
+            ConstantLanguage::Russian => "Русский",
+        }
+    }
+}
+
 impl Default for ConstantRequest {
     fn default() -> Self {
+        Self::new(ConstantLanguage::default())
+    }
+}
+
+impl ConstantRequest {
+    pub fn new(language: ConstantLanguage) -> Self {
         // The GraphQL request is now only used to fetch constants like items and heroes,
         // so here hardcode the query for simplicity.
         // Will consider making some changes if more api on https://api.stratz.com/graphiql is needed
-        Self {
-            query: r#"
+        let query = r#"
 {
   constants {
     items(language: S_CHINESE) {
       id
+      shortName
       language {
         displayName
       }
+      stat {
+        neutralItemTier
+      }
     }
     heroes(language: S_CHINESE) {
+      id
+      shortName
+      language {
+        displayName
+      }
+      facets {
+        slot
+        facetId
+      }
+    }
+    facets(language: S_CHINESE) {
       id
+      language {
+        displayName
+        description
+      }
+    }
+    abilities(language: S_CHINESE) {
+      id
+      name
+      isTalent
       language {
         displayName
       }
     }
   }
-}"#
-            .to_owned(),
+}"#;
+        Self {
+            query: query.replace("S_CHINESE", language.graphql_name()),
         }
     }
 }
@@ -40,14 +110,107 @@ pub struct ConstantResponse {
     data: ConstantData,
 }
 
+/// Display name of an ability, talents are abilities too
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AbilityConstant {
+    pub name: String,
+    pub is_talent: bool,
+}
+
+/// Icon download urls on the Valve CDN, keyed by id
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct IconUrls {
+    pub items: HashMap<i32, String>,
+    pub heroes: HashMap<i32, String>,
+    pub abilities: HashMap<i32, String>,
+}
+
+/// Display name of a hero facet, `hero_variant` in the match details
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FacetConstant {
+    pub name: String,
+    pub description: String,
+}
+
+/// Facets keyed by `hero_id`, then by `hero_variant`
+pub type HeroFacets = HashMap<i32, HashMap<i32, FacetConstant>>;
+
+pub type SpiltConstant = (HashMap<i32, String>, HashMap<i32, String>, HashMap<i32, AbilityConstant>);
+
 impl ConstantResponse {
-    pub fn spilt(self) -> (HashMap<i32, String>, HashMap<i32, String>) {
+    pub fn icon_urls(&self) -> IconUrls {
+        let constants = &self.data.constants;
+        let icon_url = |kind: &str, name: &str| format!("{}/{}/{}.png", VALVE_CDN, kind, name);
+
+        IconUrls {
+            items: constants
+                .items
+                .iter()
+                .filter_map(|item| Some((item.id, icon_url("items", item.short_name.as_ref()?))))
+                .collect(),
+            heroes: constants
+                .heroes
+                .iter()
+                .filter_map(|hero| Some((hero.id, icon_url("heroes", hero.short_name.as_ref()?))))
+                .collect(),
+            abilities: constants
+                .abilities
+                .iter()
+                .filter_map(|ability| Some((ability.id, icon_url("abilities", ability.name.as_ref()?))))
+                .collect(),
+        }
+    }
+
+    /// Tier of every neutral item and enchantment, from 1 to 5
+    pub fn neutral_tiers(&self) -> HashMap<i32, u8> {
+        self.data
+            .constants
+            .items
+            .iter()
+            .filter_map(|item| {
+                let tier = item.stat.as_ref()?.neutral_item_tier.as_deref()?;
+                Some((item.id, tier.strip_prefix("TIER_")?.parse().ok()?))
+            })
+            .collect()
+    }
+
+    pub fn facets(&self) -> HeroFacets {
+        let constants = &self.data.constants;
+        let facets = constants
+            .facets
+            .iter()
+            .filter_map(|facet| {
+                let constant = FacetConstant {
+                    name: facet.language.display_name.clone()?,
+                    description: facet.language.description.as_ref().map(Text::joined).unwrap_or_default(),
+                };
+                Some((facet.id, constant))
+            })
+            .collect::<HashMap<i32, FacetConstant>>();
+
+        constants
+            .heroes
+            .iter()
+            .map(|hero| {
+                // Stratz slots start at 0, `hero_variant` starts at 1
+                let variants = hero
+                    .facets
+                    .iter()
+                    .filter_map(|facet| Some((facet.slot + 1, facets.get(&facet.facet_id)?.clone())))
+                    .collect::<HashMap<i32, FacetConstant>>();
+                (hero.id, variants)
+            })
+            .filter(|(_, variants)| !variants.is_empty())
+            .collect()
+    }
+
+    pub fn spilt(self) -> SpiltConstant {
         let items = self
             .data
             .constants
             .items
             .iter()
-            .map(|item| (item.id, item.language.display_name.clone().unwrap_or(String::from("Unknown"))))
+            .filter_map(|item| Some((item.id, item.language.display_name.clone()?)))
             .collect::<HashMap<i32, String>>();
 
         let heroes = self
@@ -55,10 +218,24 @@ impl ConstantResponse {
             .constants
             .heroes
             .iter()
-            .map(|hero| (hero.id, hero.language.display_name.clone().unwrap_or(String::from("Unknown"))))
+            .filter_map(|hero| Some((hero.id, hero.language.display_name.clone()?)))
             .collect::<HashMap<i32, String>>();
 
-        (items, heroes)
+        let abilities = self
+            .data
+            .constants
+            .abilities
+            .iter()
+            .filter_map(|ability| {
+                let constant = AbilityConstant {
+                    name: ability.language.display_name.clone()?,
+                    is_talent: ability.is_talent.unwrap_or_default(),
+                };
+                Some((ability.id, constant))
+            })
+            .collect::<HashMap<i32, AbilityConstant>>();
+
+        (items, heroes, abilities)
     }
 }
 
@@ -71,21 +248,87 @@ struct ConstantData {
 struct Constant {
     items: Vec<Item>,
     heroes: Vec<Hero>,
+    abilities: Vec<Ability>,
+    #[serde(default)]
+    facets: Vec<Facet>,
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 struct Item {
     id: i32,
+    short_name: Option<String>,
     language: Language,
+    #[serde(default)]
+    stat: Option<ItemStat>,
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ItemStat {
+    /// `TIER_1` to `TIER_5`, `null` for regular items
+    neutral_item_tier: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 struct Hero {
     id: i32,
+    short_name: Option<String>,
     language: Language,
+    #[serde(default)]
+    facets: Vec<HeroFacet>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct HeroFacet {
+    slot: i32,
+    facet_id: i32,
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Facet {
+    id: i32,
+    language: FacetLanguage,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FacetLanguage {
+    display_name: Option<String>,
+    description: Option<Text>,
+}
+
+/// Descriptions come either as one string or as one string per line
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum Text {
+    Line(String),
+    Lines(Vec<String>),
+}
+
+impl Text {
+    fn joined(&self) -> String {
+        match self {
+            Text::Line(line) => line.clone(),
+            Text::Lines(lines) => lines.join("\n"),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Ability {
+    id: i32,
+    name: Option<String>,
+    is_talent: Option<bool>,
+    language: Language,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 struct Language {
     display_name: Option<String>,
 }
@@ -98,4 +341,40 @@ mod tests {
         let json = serde_json::to_string(&request).unwrap();
         println!("{}", json);
     }
+
+    #[test]
+    fn facets_by_variant() {
+        let json = r#"{"data": {"constants": {
+            "items": [],
+            "heroes": [{"id": 1, "shortName": "antimage", "language": {"displayName": "Anti-Mage"},
+                        "facets": [{"slot": 0, "facetId": 10}, {"slot": 1, "facetId": 11}]}],
+            "abilities": [],
+            "facets": [
+                {"id": 10, "language": {"displayName": "Magic Nullifier", "description": ["Line 1", "Line 2"]}},
+                {"id": 11, "language": {"displayName": "Mana Thirst", "description": "Single"}}
+            ]
+        }}}"#;
+        let response: super::ConstantResponse = serde_json::from_str(json).unwrap();
+        let facets = response.facets();
+        assert_eq!(facets[&1][&1].name, "Magic Nullifier");
+        assert_eq!(facets[&1][&1].description, "Line 1\nLine 2");
+        assert_eq!(facets[&1][&2].description, "Single");
+    }
+
+    #[test]
+    fn neutral_tiers() {
+        let json = r#"{"data": {"constants": {
+            "items": [
+                {"id": 1, "shortName": "blink", "language": {"displayName": "Blink Dagger"}, "stat": {"neutralItemTier": null}},
+                {"id": 2, "shortName": "trusty_shovel", "language": {"displayName": "Trusty Shovel"}, "stat": {"neutralItemTier": "TIER_1"}},
+                {"id": 3, "shortName": "giants_ring", "language": {"displayName": "Giant's Ring"}}
+            ],
+            "heroes": [],
+            "abilities": []
+        }}}"#;
+        let response: super::ConstantResponse = serde_json::from_str(json).unwrap();
+        let tiers = response.neutral_tiers();
+        assert_eq!(tiers.len(), 1);
+        assert_eq!(tiers[&2], 1);
+    }
 }
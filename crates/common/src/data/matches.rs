@@ -1,9 +1,14 @@
-use std::collections::VecDeque;
-
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use snafu::OptionExt;
 
-use crate::error::NoneValueSnafu;
+use super::building::{BarracksStatus, TeamBuildings, TowerStatus};
+use crate::{error::PlayerNotInMatchSnafu, metrics::PlayerMetrics};
+
+/// `account_id` of players who keep their match data private
+pub const ANONYMOUS_ACCOUNT_ID: i64 = 4294967295;
+
+/// `GetMatchHistory` status when the account hasn't turned on "Expose Public Match Data"
+const HISTORY_NOT_ALLOWED: i32 = 15;
 
 #[derive(Deserialize, Debug)]
 pub struct MatchHistoryResponse {
@@ -14,34 +19,33 @@ impl MatchHistoryResponse {
     pub fn match_seq_num(&self) -> Vec<i64> {
         self.result.matches.iter().map(|m| m.match_seq_num).collect()
     }
+
+    /// `match_seq_num` of `match_id` if it is in this page
+    pub fn match_seq_num_of(
+        &self,
+        match_id: i64,
+    ) -> Option<i64> {
+        self.result.matches.iter().find(|m| m.match_id == match_id).map(|m| m.match_seq_num)
+    }
+
+    /// Steam refused to list the history because the account's match data isn't public
+    pub fn is_not_allowed(&self) -> bool {
+        self.result.status == HISTORY_NOT_ALLOWED
+    }
 }
 
 #[derive(Deserialize, Debug)]
 struct MatchHistoryResult {
+    /// Missing when `status` is an error
+    #[serde(default)]
     matches: Vec<Match>,
     status: i32,
-    num_results: i32,
-    total_results: i32,
-    results_remaining: i32,
 }
 
 #[derive(Deserialize, Debug)]
 struct Match {
     match_id: i64,
     match_seq_num: i64,
-    start_time: i64,
-    lobby_type: i32,
-    radiant_team_id: i32,
-    dire_team_id: i32,
-    players: Vec<Player>,
-}
-
-#[derive(Deserialize, Debug)]
-struct Player {
-    account_id: i64,
-    player_slot: i32,
-    hero_id: i32,
-    hero_variant: i32,
 }
 
 #[derive(Deserialize, Debug)]
@@ -50,26 +54,22 @@ pub struct MatchDetailResponse {
 }
 
 impl MatchDetailResponse {
-    pub fn views(
-        self,
-        account_id: i64,
-    ) -> VecDeque<MatchDetailView> {
-        let matches = self.result.matches;
-        matches
-            .into_iter()
-            .map(|m| MatchDetailView::from_match_detail(m, account_id).expect("No account_id matched"))
-            .collect::<VecDeque<MatchDetailView>>()
+    pub fn matches(&self) -> &[MatchDetail] {
+        &self.result.matches
+    }
+
+    pub fn into_matches(self) -> Vec<MatchDetail> {
+        self.result.matches
     }
 }
 
 #[derive(Deserialize, Debug)]
 struct MatchDetailResult {
-    status: i32,
     matches: Vec<MatchDetail>,
 }
 
-#[derive(Deserialize, Debug)]
-struct MatchDetail {
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MatchDetail {
     players: Vec<PlayerDetail>,
     radiant_win: bool,
     duration: i32,
@@ -81,50 +81,183 @@ struct MatchDetail {
     game_mode: GameMode,
     radiant_score: i32,
     dire_score: i32,
+    // Missing in matches archived before these fields were tracked
+    #[serde(default)]
+    tower_status_radiant: Option<TowerStatus>,
+    #[serde(default)]
+    tower_status_dire: Option<TowerStatus>,
+    #[serde(default)]
+    barracks_status_radiant: Option<BarracksStatus>,
+    #[serde(default)]
+    barracks_status_dire: Option<BarracksStatus>,
+    /// Only present in draft modes like Captain's Mode and ranked All Pick
+    #[serde(default)]
+    picks_bans: Vec<PickBan>,
 }
 
+impl MatchDetail {
+    pub fn match_id(&self) -> i64 {
+        self.match_id
+    }
+
+    pub fn match_seq_num(&self) -> i64 {
+        self.match_seq_num
+    }
+
+    pub fn players(&self) -> &[PlayerDetail] {
+        &self.players
+    }
+
+    /// Index of `account_id` in `players()`
+    pub fn player_index_of(
+        &self,
+        account_id: i64,
+    ) -> Option<usize> {
+        self.players.iter().position(|p| p.account_id == account_id)
+    }
+
+    /// Some players hide their match data, when the tracked account is missing from its own
+    /// history it is most likely one of them
+    pub fn has_anonymous_player(&self) -> bool {
+        self.players.iter().any(|p| p.is_anonymous())
+    }
+
+    pub fn radiant_win(&self) -> bool {
+        self.radiant_win
+    }
+
+    pub fn start_time_col(&self) -> String {
+        let date_time = chrono::DateTime::from_timestamp(self.start_time, 0);
+        date_time
+            .map(|dt| chrono::DateTime::<chrono::Local>::from(dt).format("%Y/%m/%d %H:%M:%S").to_string())
+            .unwrap_or(String::from("Unknown"))
+    }
+
+    pub fn duration_col(&self) -> String {
+        format!("{}m{}s", self.duration / 60, self.duration % 60)
+    }
+
+    pub fn game_mode_col(&self) -> String {
+        format!("{:?}", self.game_mode)
+    }
+
+    /// Radiant : Dire kills
+    pub fn score_col(&self) -> String {
+        format!("{} : {}", self.radiant_score, self.dire_score)
+    }
+
+    /// Picks and bans in draft order, empty when the game mode has no draft
+    pub fn picks_bans(&self) -> Vec<PickBan> {
+        let mut picks_bans = self.picks_bans.clone();
+        picks_bans.sort_by_key(|pb| pb.order);
+        picks_bans
+    }
+
+    /// Radiant and dire buildings at the end of the game
+    pub fn buildings(&self) -> Option<(TeamBuildings, TeamBuildings)> {
+        let radiant = TeamBuildings {
+            towers: self.tower_status_radiant?,
+            barracks: self.barracks_status_radiant?,
+        };
+        let dire = TeamBuildings {
+            towers: self.tower_status_dire?,
+            barracks: self.barracks_status_dire?,
+        };
+        Some((radiant, dire))
+    }
+
+    pub fn player_metrics(
+        &self,
+        player: &PlayerDetail,
+    ) -> PlayerMetrics {
+        let is_radiant = player.is_radiant();
+        let team_kills = if is_radiant { self.radiant_score } else { self.dire_score };
+        let team = self.players.iter().filter(|p| p.is_radiant() == is_radiant);
+        PlayerMetrics::new(player, team, team_kills, self.duration)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PickBan {
+    pub is_pick: bool,
+    pub hero_id: i32,
+    /// 0 for radiant, 1 for dire
+    pub team: i32,
+    pub order: i32,
+}
+
+impl PickBan {
+    pub fn is_radiant(&self) -> bool {
+        self.team == 0
+    }
+}
+
+/// A `MatchDetail` seen from the perspective of the tracked account
+#[derive(Debug, Clone)]
 pub struct MatchDetailView {
-    win: bool,
-    duration: i32,
-    start_time: i64,
-    game_mode: GameMode,
-    radiant_score: i32,
-    dire_score: i32,
-    player_detail: PlayerDetail,
+    detail: MatchDetail,
+    /// Index of the tracked account in `detail.players`
+    player_index: usize,
 }
 
 impl MatchDetailView {
-    fn from_match_detail(
+    pub fn from_match_detail(
         match_detail: MatchDetail,
         account_id: i64,
     ) -> Result<Self, crate::Error> {
-        let player_detail = match_detail
-            .players
-            .into_iter()
-            .find(|p| p.account_id == account_id)
-            .context(NoneValueSnafu { expected: "PlayerDetail" })?;
+        let player_index = match_detail.player_index_of(account_id).context(PlayerNotInMatchSnafu {
+            account_id,
+            match_id: match_detail.match_id,
+        })?;
         Ok(Self {
-            win: match_detail.radiant_win && player_detail.player_slot < 128 || !match_detail.radiant_win && player_detail.player_slot >= 128,
-            duration: match_detail.duration,
-            start_time: match_detail.start_time,
-            game_mode: match_detail.game_mode,
-            radiant_score: match_detail.radiant_score,
-            dire_score: match_detail.dire_score,
-            player_detail,
+            detail: match_detail,
+            player_index,
         })
     }
 
-    pub fn participation_rate(&self) -> f32 {
-        let total = if self.is_radiant() { self.radiant_score } else { self.dire_score };
-        self.player_detail.kills as f32 / total as f32
+    pub fn metrics(&self) -> PlayerMetrics {
+        self.detail.player_metrics(self.player_detail())
+    }
+
+    pub fn match_detail(&self) -> &MatchDetail {
+        &self.detail
+    }
+
+    pub fn match_id(&self) -> i64 {
+        self.detail.match_id
+    }
+
+    pub fn win(&self) -> bool {
+        self.detail.radiant_win == self.is_radiant()
+    }
+
+    pub fn duration(&self) -> i32 {
+        self.detail.duration
+    }
+
+    pub fn start_time(&self) -> i64 {
+        self.detail.start_time
+    }
+
+    /// Local date the match started on
+    pub fn start_date(&self) -> Option<chrono::NaiveDate> {
+        chrono::DateTime::from_timestamp(self.detail.start_time, 0).map(|dt| chrono::DateTime::<chrono::Local>::from(dt).date_naive())
+    }
+
+    pub fn game_mode(&self) -> GameMode {
+        self.detail.game_mode
+    }
+
+    pub fn lobby_type(&self) -> LobbyType {
+        self.detail.lobby_type
     }
 
     pub fn is_radiant(&self) -> bool {
-        self.player_detail.player_slot < 128
+        self.player_detail().is_radiant()
     }
 
     pub fn win_col(&self) -> String {
-        if self.win {
+        if self.win() {
             "Win".to_owned()
         } else {
             "Lose".to_owned()
@@ -132,30 +265,37 @@ impl MatchDetailView {
     }
 
     pub fn start_time_col(&self) -> String {
-        let date_time = chrono::DateTime::from_timestamp(self.start_time, 0);
-        date_time
-            .map(|dt| chrono::DateTime::<chrono::Local>::from(dt).format("%Y/%m/%d %H:%M:%S").to_string())
-            .unwrap_or(String::from("Unknown"))
+        self.detail.start_time_col()
     }
 
     pub fn duration_col(&self) -> String {
-        format!("{}m{}s", self.duration / 60, self.duration % 60)
+        self.detail.duration_col()
     }
 
     pub fn game_mode_col(&self) -> String {
-        format!("{:?}", self.game_mode)
+        self.detail.game_mode_col()
     }
 
     pub fn player_detail_col(&self) -> String {
-        format!("{:#?}", self.player_detail)
+        format!("{:#?}", self.player_detail())
+    }
+
+    pub fn kda_col(&self) -> String {
+        let player = self.player_detail();
+        format!("{}/{}/{}", player.kills, player.deaths, player.assists)
+    }
+
+    pub fn player_detail(&self) -> &PlayerDetail {
+        &self.detail.players[self.player_index]
     }
 
-    pub fn player_detail(&self) -> PlayerDetail {
-        self.player_detail.clone()
+    /// Index of the tracked account in `match_detail().players()`
+    pub fn player_index(&self) -> usize {
+        self.player_index
     }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct PlayerDetail {
     pub account_id: i64,
     pub player_slot: i32,
@@ -171,6 +311,9 @@ pub struct PlayerDetail {
     pub backpack_1: i32,
     pub backpack_2: i32,
     pub item_neutral: i32,
+    /// Enchantment of the neutral item, only in newer matches
+    #[serde(default)]
+    pub item_neutral2: i32,
     pub kills: i32,
     pub deaths: i32,
     pub assists: i32,
@@ -189,16 +332,97 @@ pub struct PlayerDetail {
     pub hero_healing: i32,
     pub gold: i32,
     pub gold_spent: i32,
+    /// Skill build in upgrade order
+    #[serde(default)]
+    pub ability_upgrades: Vec<AbilityUpgrade>,
+    /// Controlled units with their own inventory, e.g. Lone Druid's Spirit Bear
+    #[serde(default)]
+    pub additional_units: Vec<AdditionalUnit>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AdditionalUnit {
+    /// Internal unit name like `spirit_bear`
+    pub unitname: String,
+    // Slots are left out for some summons, default them so one unit can't fail the whole match
+    #[serde(default)]
+    pub item_0: i32,
+    #[serde(default)]
+    pub item_1: i32,
+    #[serde(default)]
+    pub item_2: i32,
+    #[serde(default)]
+    pub item_3: i32,
+    #[serde(default)]
+    pub item_4: i32,
+    #[serde(default)]
+    pub item_5: i32,
+    #[serde(default)]
+    pub backpack_0: i32,
+    #[serde(default)]
+    pub backpack_1: i32,
+    #[serde(default)]
+    pub backpack_2: i32,
+    #[serde(default)]
+    pub item_neutral: i32,
+}
+
+impl AdditionalUnit {
+    /// Inventory, backpack and neutral slot, in that order. Empty slots are `0`.
+    pub fn items(&self) -> [i32; 10] {
+        [
+            self.item_0,
+            self.item_1,
+            self.item_2,
+            self.item_3,
+            self.item_4,
+            self.item_5,
+            self.backpack_0,
+            self.backpack_1,
+            self.backpack_2,
+            self.item_neutral,
+        ]
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AbilityUpgrade {
+    pub ability: i32,
+    /// Seconds since the horn
+    pub time: i32,
+    /// Hero level the point was spent at
+    pub level: i32,
 }
 
 impl PlayerDetail {
-    pub fn gold_damage_ratio(&self) -> f32 {
-        (self.gold / self.hero_damage) as f32
+    /// Inventory, backpack, neutral and enchantment slot, in that order. Empty slots are `0`.
+    pub fn items(&self) -> [i32; 11] {
+        [
+            self.item_0,
+            self.item_1,
+            self.item_2,
+            self.item_3,
+            self.item_4,
+            self.item_5,
+            self.backpack_0,
+            self.backpack_1,
+            self.backpack_2,
+            self.item_neutral,
+            self.item_neutral2,
+        ]
+    }
+
+    pub fn is_radiant(&self) -> bool {
+        self.player_slot < 128
+    }
+
+    pub fn is_anonymous(&self) -> bool {
+        self.account_id == ANONYMOUS_ACCOUNT_ID
     }
 }
 
-#[derive(Debug)]
-enum LobbyType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LobbyType {
     Invalid = -1,
     PublicMatchmaking = 0,
     Practice = 1,
@@ -211,6 +435,33 @@ enum LobbyType {
     SoloMid1v1 = 8,
 }
 
+impl LobbyType {
+    pub const ALL: [LobbyType; 10] = [
+        LobbyType::Invalid,
+        LobbyType::PublicMatchmaking,
+        LobbyType::Practice,
+        LobbyType::Tournament,
+        LobbyType::Tutorial,
+        LobbyType::CoopWithBots,
+        LobbyType::TeamMatch,
+        LobbyType::SoloQueue,
+        LobbyType::Ranked,
+        LobbyType::SoloMid1v1,
+    ];
+}
+
+impl Serialize for LobbyType {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i32(*self as i32)
+    }
+}
+
 impl<'de> Deserialize<'de> for LobbyType {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -233,7 +484,7 @@ impl<'de> Deserialize<'de> for LobbyType {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameMode {
     None = 0,
     AllPick = 1,
@@ -259,6 +510,45 @@ pub enum GameMode {
     Turbo = 23,
 }
 
+impl GameMode {
+    pub const ALL: [GameMode; 22] = [
+        GameMode::None,
+        GameMode::AllPick,
+        GameMode::CaptainMode,
+        GameMode::RandomDraft,
+        GameMode::SingleDraft,
+        GameMode::AllRandom,
+        GameMode::Intro,
+        GameMode::Diretide,
+        GameMode::ReverseCaptainMode,
+        GameMode::Greeviling,
+        GameMode::Tutorial,
+        GameMode::MidOnly,
+        GameMode::LeastPlayed,
+        GameMode::NewPlayerPool,
+        GameMode::CompendiumMatchmaking,
+        GameMode::CoopVsBots,
+        GameMode::CaptainsDraft,
+        GameMode::AbilityDraft,
+        GameMode::AllRandomDeathMatch,
+        GameMode::OneVsOneMid,
+        GameMode::RankedMatchmaking,
+        GameMode::Turbo,
+    ];
+}
+
+impl Serialize for GameMode {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i32(*self as i32)
+    }
+}
+
 impl<'de> Deserialize<'de> for GameMode {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -293,8 +583,8 @@ impl<'de> Deserialize<'de> for GameMode {
     }
 }
 
-#[derive(Debug, Clone)]
-enum LeaverStatus {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaverStatus {
     None = 0,
     Disconnected = 1,
     DisconnectedTooLong = 2,
@@ -304,6 +594,18 @@ enum LeaverStatus {
     NeverConnectedTooLong = 6,
 }
 
+impl Serialize for LeaverStatus {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i32(*self as i32)
+    }
+}
+
 impl<'de> Deserialize<'de> for LeaverStatus {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -322,3 +624,29 @@ impl<'de> Deserialize<'de> for LeaverStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_not_allowed() {
+        let json = r#"{"result":{"status":15,"statusDetail":"Cannot get match history for a user that hasn't allowed it."}}"#;
+        let response: MatchHistoryResponse = serde_json::from_str(json).unwrap();
+        assert!(response.is_not_allowed());
+        assert!(response.match_seq_num().is_empty());
+
+        // A new account has an empty but public history
+        let json = r#"{"result":{"status":1,"num_results":0,"total_results":0,"results_remaining":0,"matches":[]}}"#;
+        let response: MatchHistoryResponse = serde_json::from_str(json).unwrap();
+        assert!(!response.is_not_allowed());
+        assert!(response.match_seq_num().is_empty());
+    }
+
+    #[test]
+    fn unit_without_slots() {
+        let json = r#"{"unitname":"spirit_bear","item_0":1}"#;
+        let unit: AdditionalUnit = serde_json::from_str(json).unwrap();
+        assert_eq!(unit.items(), [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+}
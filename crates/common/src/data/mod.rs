@@ -1,4 +1,6 @@
 //! Reference: https://github.com/SteamDatabase/SteamTracking
 
+pub mod building;
 pub mod constant;
 pub mod matches;
+pub mod opendota;
@@ -0,0 +1,10 @@
+//! Reference: https://docs.opendota.com
+
+use serde::Deserialize;
+
+/// The part of `GET /api/matches/{match_id}` needed to look the match up on the Steam API
+#[derive(Deserialize, Debug)]
+pub struct OpenDotaMatch {
+    pub match_id: i64,
+    pub match_seq_num: Option<i64>,
+}
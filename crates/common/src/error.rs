@@ -11,4 +11,11 @@ pub enum Error {
         #[snafu(implicit)]
         location: Location,
     },
+    #[snafu(display("PlayerNotInMatch: account {} did not play in match {}", account_id, match_id))]
+    PlayerNotInMatch {
+        account_id: i64,
+        match_id: i64,
+        #[snafu(implicit)]
+        location: Location,
+    },
 }
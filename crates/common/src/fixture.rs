@@ -0,0 +1,63 @@
+//! Matches built from JSON for tests, shared with the other crates through the `fixture` feature
+
+use serde_json::{json, Value};
+
+use crate::data::matches::{MatchDetail, MatchDetailView, PlayerDetail};
+
+/// Account 1 on radiant, `fields` replace any of the defaults
+pub fn player(fields: Value) -> PlayerDetail {
+    serde_json::from_value(player_json(fields)).unwrap()
+}
+
+/// A 30 minute ranked match with `players`, `fields` replace any of the defaults
+pub fn match_detail(
+    players: Vec<Value>,
+    fields: Value,
+) -> MatchDetail {
+    let mut detail = json!({
+        "players": players,
+        "radiant_win": true, "duration": 1800, "start_time": 0, "match_id": 1,
+        "match_seq_num": 1, "first_blood_time": 60, "lobby_type": 7, "game_mode": 22,
+        "radiant_score": 30, "dire_score": 20,
+    });
+    merge(&mut detail, fields);
+    serde_json::from_value(detail).unwrap()
+}
+
+/// The tracked account is player 1 on radiant, GPM grows with `kills`
+pub fn view(
+    match_id: i64,
+    start_time: i64,
+    win: bool,
+    kills: i32,
+) -> MatchDetailView {
+    let player = player_json(json!({"kills": kills, "gold_per_min": 400 + kills * 20}));
+    let fields = json!({
+        "radiant_win": win, "start_time": start_time, "match_id": match_id, "match_seq_num": match_id,
+    });
+    MatchDetailView::from_match_detail(match_detail(vec![player], fields), 1).unwrap()
+}
+
+/// `player` as JSON, e.g. to put several of them in `match_detail`
+pub fn player_json(fields: Value) -> Value {
+    let mut player = json!({
+        "account_id": 1, "player_slot": 0, "hero_id": 1, "hero_variant": 1,
+        "item_0": 0, "item_1": 0, "item_2": 0, "item_3": 0, "item_4": 0, "item_5": 0,
+        "backpack_0": 0, "backpack_1": 0, "backpack_2": 0, "item_neutral": 0,
+        "kills": 10, "deaths": 5, "assists": 5, "leaver_status": 0,
+        "last_hits": 300, "denies": 10, "gold_per_min": 600, "xp_per_min": 700, "level": 25,
+        "net_worth": 20000, "aghanims_scepter": 0, "aghanims_shard": 0, "moonshard": 0,
+        "hero_damage": 30000, "tower_damage": 0, "hero_healing": 0, "gold": 0, "gold_spent": 0,
+    });
+    merge(&mut player, fields);
+    player
+}
+
+fn merge(
+    value: &mut Value,
+    fields: Value,
+) {
+    if let (Some(value), Value::Object(fields)) = (value.as_object_mut(), fields) {
+        value.extend(fields);
+    }
+}
@@ -0,0 +1,214 @@
+//! Personal goals checked against the tracked account's recent matches
+
+use serde::{Deserialize, Serialize};
+
+use crate::{data::matches::MatchDetailView, metrics};
+
+/// Per-game numbers a goal can be set on. The Steam API has no per-minute timeline, so e.g. CS at
+/// 10 minutes is approximated by `CsPerMin`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GoalMetric {
+    LastHits,
+    CsPerMin,
+    Kills,
+    Deaths,
+    Assists,
+    Kda,
+    GoldPerMin,
+    XpPerMin,
+    HeroDamage,
+    NetWorth,
+}
+
+impl GoalMetric {
+    pub const ALL: [GoalMetric; 10] = [
+        GoalMetric::LastHits,
+        GoalMetric::CsPerMin,
+        GoalMetric::Kills,
+        GoalMetric::Deaths,
+        GoalMetric::Assists,
+        GoalMetric::Kda,
+        GoalMetric::GoldPerMin,
+        GoalMetric::XpPerMin,
+        GoalMetric::HeroDamage,
+        GoalMetric::NetWorth,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GoalMetric::LastHits => "正补",
+            GoalMetric::CsPerMin => "每分钟正补",
+            GoalMetric::Kills => "击杀",
+            GoalMetric::Deaths => "死亡",
+            GoalMetric::Assists => "助攻",
+            GoalMetric::Kda => "KDA",
+            GoalMetric::GoldPerMin => "GPM",
+            GoalMetric::XpPerMin => "XPM",
+            GoalMetric::HeroDamage => "英雄伤害",
+            GoalMetric::NetWorth => "净资产",
+        }
+    }
+
+    pub fn value(
+        &self,
+        view: &MatchDetailView,
+    ) -> f32 {
+        let player = view.player_detail();
+        match self {
+            GoalMetric::LastHits => player.last_hits as f32,
+            GoalMetric::CsPerMin => metrics::cs_per_min(player.last_hits, view.duration()),
+            GoalMetric::Kills => player.kills as f32,
+            GoalMetric::Deaths => player.deaths as f32,
+            GoalMetric::Assists => player.assists as f32,
+            GoalMetric::Kda => metrics::kda(player.kills, player.deaths, player.assists),
+            GoalMetric::GoldPerMin => player.gold_per_min as f32,
+            GoalMetric::XpPerMin => player.xp_per_min as f32,
+            GoalMetric::HeroDamage => player.hero_damage as f32,
+            GoalMetric::NetWorth => player.net_worth as f32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    AtLeast,
+    AtMost,
+}
+
+impl Comparison {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::AtLeast => "≥",
+            Comparison::AtMost => "≤",
+        }
+    }
+
+    pub fn holds(
+        &self,
+        value: f32,
+        target: f32,
+    ) -> bool {
+        match self {
+            Comparison::AtLeast => value >= target,
+            Comparison::AtMost => value <= target,
+        }
+    }
+}
+
+/// e.g. "GPM ≥ 550 on Anti-Mage and Medusa over the last 20 games"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub name: String,
+    pub metric: GoalMetric,
+    pub comparison: Comparison,
+    pub target: f32,
+    /// Only games on these heroes count, every hero when empty
+    #[serde(default)]
+    pub heroes: Vec<i32>,
+    /// How many of the latest counted games are evaluated
+    pub window: usize,
+}
+
+impl Default for Goal {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            metric: GoalMetric::Deaths,
+            comparison: Comparison::AtMost,
+            target: 5.0,
+            heroes: Vec::new(),
+            window: 20,
+        }
+    }
+}
+
+/// One evaluated game
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GoalResult {
+    pub match_id: i64,
+    pub value: f32,
+    pub hit: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GoalProgress {
+    /// Newest first
+    pub history: Vec<GoalResult>,
+    /// Mean value over `history`
+    pub average: f32,
+    /// The average itself meets the goal
+    pub average_hit: bool,
+}
+
+impl GoalProgress {
+    /// Share of the games in `history` that met the goal
+    pub fn hit_rate(&self) -> f32 {
+        let hits = self.history.iter().filter(|result| result.hit).count();
+        metrics::ratio(hits as i32, self.history.len() as i32)
+    }
+}
+
+impl Goal {
+    /// `views` newest first, as returned by `MatchArchive::views`
+    pub fn evaluate<'a>(
+        &self,
+        views: impl IntoIterator<Item = &'a MatchDetailView>,
+    ) -> GoalProgress {
+        let history = views
+            .into_iter()
+            .filter(|view| self.heroes.is_empty() || self.heroes.contains(&view.player_detail().hero_id))
+            .take(self.window)
+            .map(|view| {
+                let value = self.metric.value(view);
+                GoalResult {
+                    match_id: view.match_id(),
+                    value,
+                    hit: self.comparison.holds(value, self.target),
+                }
+            })
+            .collect::<Vec<_>>();
+        let average = if history.is_empty() {
+            0.0
+        } else {
+            history.iter().map(|result| result.value).sum::<f32>() / history.len() as f32
+        };
+        GoalProgress {
+            average_hit: !history.is_empty() && self.comparison.holds(average, self.target),
+            history,
+            average,
+        }
+    }
+
+    /// `GPM ≥ 550`
+    pub fn condition(&self) -> String {
+        format!("{} {} {}", self.metric.label(), self.comparison.symbol(), self.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::view;
+
+    #[test]
+    fn evaluate_window() {
+        let views = [view(4, 3, true, 12), view(3, 2, true, 2), view(2, 1, false, 10), view(1, 0, true, 10)];
+        let goal = Goal {
+            metric: GoalMetric::GoldPerMin,
+            comparison: Comparison::AtLeast,
+            target: 550.0,
+            window: 3,
+            ..Default::default()
+        };
+        let progress = goal.evaluate(&views);
+
+        let hits = progress.history.iter().map(|result| (result.match_id, result.hit)).collect::<Vec<_>>();
+        assert_eq!(hits, [(4, true), (3, false), (2, true)]);
+        assert_eq!(progress.average, 560.0);
+        assert!(progress.average_hit);
+        assert_eq!(progress.hit_rate(), 2.0 / 3.0);
+
+        let other_hero = Goal { heroes: vec![2], ..goal };
+        assert!(other_hero.evaluate(&views).history.is_empty());
+    }
+}
@@ -0,0 +1,9 @@
+//! Helpers for the HTML pages and exports
+
+/// Escape `text` for HTML element content and quoted attribute values
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,3 +1,13 @@
+pub mod compare;
 pub mod data;
 pub mod error;
+#[cfg(any(test, feature = "fixture"))]
+pub mod fixture;
+pub mod goal;
+pub mod html;
+pub mod links;
+pub mod metrics;
+pub mod report;
+pub mod stats;
+pub mod streak;
 pub use error::Error;
@@ -0,0 +1,43 @@
+//! Links to the same match or player on the usual stats sites
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalSite {
+    OpenDota,
+    Dotabuff,
+    Stratz,
+}
+
+impl ExternalSite {
+    pub const ALL: [ExternalSite; 3] = [ExternalSite::OpenDota, ExternalSite::Dotabuff, ExternalSite::Stratz];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExternalSite::OpenDota => "OpenDota",
+            ExternalSite::Dotabuff => "Dotabuff",
+            ExternalSite::Stratz => "Stratz",
+        }
+    }
+
+    fn base_url(&self) -> &'static str {
+        match self {
+            ExternalSite::OpenDota => "https://www.opendota.com",
+            ExternalSite::Dotabuff => "https://www.dotabuff.com",
+            ExternalSite::Stratz => "https://stratz.com",
+        }
+    }
+
+    pub fn match_url(
+        &self,
+        match_id: i64,
+    ) -> String {
+        format!("{}/matches/{}", self.base_url(), match_id)
+    }
+
+    /// `account_id` is the 32-bit id used by the Dota API, not the 64-bit Steam id
+    pub fn player_url(
+        &self,
+        account_id: i64,
+    ) -> String {
+        format!("{}/players/{}", self.base_url(), account_id)
+    }
+}
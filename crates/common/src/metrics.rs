@@ -0,0 +1,181 @@
+//! Derived per-player metrics. Every calculation is zero-safe: a zero denominator gives `0.0`
+//! instead of a panic or `NaN`.
+
+use crate::data::matches::PlayerDetail;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PlayerMetrics {
+    pub kda: f32,
+    /// (K + A) / team kills
+    pub kill_participation: f32,
+    /// Hero damage per gold earned over the game
+    pub damage_per_gold: f32,
+    pub cs_per_min: f32,
+    /// Player net worth / team net worth
+    pub net_worth_share: f32,
+    /// Player hero damage / team hero damage
+    pub damage_share: f32,
+}
+
+impl PlayerMetrics {
+    /// `team` is every player on the same side, including `player` itself
+    pub fn new<'a>(
+        player: &PlayerDetail,
+        team: impl IntoIterator<Item = &'a PlayerDetail>,
+        team_kills: i32,
+        duration: i32,
+    ) -> Self {
+        let (team_net_worth, team_damage) = team
+            .into_iter()
+            .fold((0, 0), |(net_worth, damage), p| (net_worth + p.net_worth, damage + p.hero_damage));
+
+        Self {
+            kda: kda(player.kills, player.deaths, player.assists),
+            kill_participation: kill_participation(player.kills, player.assists, team_kills),
+            damage_per_gold: damage_per_gold(player.hero_damage, gold_earned(player.gold_per_min, duration)),
+            cs_per_min: cs_per_min(player.last_hits, duration),
+            net_worth_share: share(player.net_worth, team_net_worth),
+            damage_share: share(player.hero_damage, team_damage),
+        }
+    }
+
+    /// Rough 0 to 100 performance score to rank a player's games against each other. KDA counts
+    /// up to 10, damage and net worth shares up to twice the fair share of a 5 player team.
+    pub fn score(&self) -> f32 {
+        let kda = (self.kda / 10.0).min(1.0);
+        let damage = (self.damage_share * 2.5).min(1.0);
+        let net_worth = (self.net_worth_share * 2.5).min(1.0);
+        40.0 * kda + 30.0 * self.kill_participation + 15.0 * damage + 15.0 * net_worth
+    }
+}
+
+/// (K + A) / D, deathless games count as one death
+pub fn kda(
+    kills: i32,
+    deaths: i32,
+    assists: i32,
+) -> f32 {
+    (kills + assists) as f32 / deaths.max(1) as f32
+}
+
+pub fn kill_participation(
+    kills: i32,
+    assists: i32,
+    team_kills: i32,
+) -> f32 {
+    ratio(kills + assists, team_kills).min(1.0)
+}
+
+pub fn damage_per_gold(
+    hero_damage: i32,
+    gold: i32,
+) -> f32 {
+    ratio(hero_damage, gold)
+}
+
+/// Gold earned over a game of `duration` seconds. Unlike net worth, gold spent on buybacks and
+/// consumables still counts.
+pub fn gold_earned(
+    gold_per_min: i32,
+    duration: i32,
+) -> i32 {
+    gold_per_min * duration / 60
+}
+
+/// `duration` in seconds
+pub fn cs_per_min(
+    last_hits: i32,
+    duration: i32,
+) -> f32 {
+    ratio(last_hits * 60, duration)
+}
+
+pub fn share(
+    part: i32,
+    total: i32,
+) -> f32 {
+    ratio(part, total)
+}
+
+/// `numerator / denominator`, `0.0` when the denominator is not positive
+pub fn ratio(
+    numerator: i32,
+    denominator: i32,
+) -> f32 {
+    if denominator <= 0 {
+        return 0.0;
+    }
+    numerator as f32 / denominator as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture;
+
+    fn player(
+        kills: i32,
+        deaths: i32,
+        assists: i32,
+        net_worth: i32,
+        hero_damage: i32,
+    ) -> PlayerDetail {
+        fixture::player(serde_json::json!({
+            "kills": kills, "deaths": deaths, "assists": assists, "net_worth": net_worth, "hero_damage": hero_damage,
+        }))
+    }
+
+    #[test]
+    fn kda_without_deaths() {
+        assert_eq!(kda(10, 0, 5), 15.0);
+        assert_eq!(kda(10, 3, 5), 5.0);
+    }
+
+    #[test]
+    fn kill_participation_counts_assists() {
+        assert_eq!(kill_participation(5, 5, 20), 0.5);
+        assert_eq!(kill_participation(5, 5, 0), 0.0);
+    }
+
+    #[test]
+    fn zero_denominators() {
+        assert_eq!(damage_per_gold(1000, 0), 0.0);
+        assert_eq!(cs_per_min(100, 0), 0.0);
+        assert_eq!(share(0, 0), 0.0);
+    }
+
+    #[test]
+    fn cs_per_min_uses_seconds() {
+        assert_eq!(cs_per_min(300, 1800), 10.0);
+    }
+
+    #[test]
+    fn support_without_damage() {
+        let support = player(0, 10, 20, 5000, 0);
+        let carry = player(15, 2, 5, 25000, 40000);
+        let metrics = PlayerMetrics::new(&support, [&support, &carry], 25, 2400);
+
+        assert_eq!(metrics.kda, 2.0);
+        assert_eq!(metrics.kill_participation, 0.8);
+        assert_eq!(metrics.damage_per_gold, 0.0);
+        assert_eq!(metrics.net_worth_share, 5000.0 / 30000.0);
+        assert_eq!(metrics.damage_share, 0.0);
+
+        // 600 GPM over 40 minutes
+        let metrics = PlayerMetrics::new(&carry, [&support, &carry], 25, 2400);
+        assert_eq!(metrics.damage_per_gold, 40000.0 / 24000.0);
+    }
+
+    #[test]
+    fn score_range() {
+        assert_eq!(PlayerMetrics::default().score(), 0.0);
+        let perfect = PlayerMetrics {
+            kda: 20.0,
+            kill_participation: 1.0,
+            net_worth_share: 0.5,
+            damage_share: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(perfect.score(), 100.0);
+    }
+}
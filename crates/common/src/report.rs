@@ -0,0 +1,344 @@
+//! Summaries of the tracked account's play sessions and calendar days
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{
+    data::matches::MatchDetailView,
+    html::escape_html,
+    stats::{self, HeroStats, Record},
+};
+
+/// Seconds between the end of a game and the start of the next one that start a new session
+pub const SESSION_GAP: i64 = 2 * 60 * 60;
+
+/// One game ranked by `PlayerMetrics::score`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameScore {
+    pub match_id: i64,
+    pub hero_id: i32,
+    pub win: bool,
+    pub kills: i32,
+    pub deaths: i32,
+    pub assists: i32,
+    pub score: f32,
+}
+
+impl GameScore {
+    fn new(view: &MatchDetailView) -> Self {
+        let player = view.player_detail();
+        Self {
+            match_id: view.match_id(),
+            hero_id: player.hero_id,
+            win: view.win(),
+            kills: player.kills,
+            deaths: player.deaths,
+            assists: player.assists,
+            score: view.metrics().score(),
+        }
+    }
+
+    fn describe(
+        &self,
+        hero_name: &impl Fn(i32) -> String,
+    ) -> String {
+        format!(
+            "{} {}/{}/{} ({}, 评分 {:.0})",
+            hero_name(self.hero_id),
+            self.kills,
+            self.deaths,
+            self.assists,
+            if self.win { "Win" } else { "Lose" },
+            self.score
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    /// Start of the first game, unix seconds
+    pub start_time: i64,
+    /// End of the last game, unix seconds
+    pub end_time: i64,
+    pub record: Record,
+    /// Seconds spent in game, the gaps between games not included
+    pub time_played: i64,
+    pub best: GameScore,
+    pub worst: GameScore,
+    pub heroes: Vec<HeroStats>,
+}
+
+impl Report {
+    /// `None` without any match
+    pub fn new(views: &[&MatchDetailView]) -> Option<Self> {
+        let scores = views.iter().map(|view| GameScore::new(view)).collect::<Vec<_>>();
+        let best = scores.iter().max_by(|a, b| a.score.total_cmp(&b.score))?.clone();
+        let worst = scores.iter().min_by(|a, b| a.score.total_cmp(&b.score))?.clone();
+        Some(Self {
+            start_time: views.iter().map(|view| view.start_time()).min()?,
+            end_time: views.iter().map(|view| end_time(view)).max()?,
+            record: Record::of(views.iter().copied()),
+            time_played: views.iter().map(|view| i64::from(view.duration())).sum(),
+            best,
+            worst,
+            heroes: stats::hero_stats(views.iter().copied()),
+        })
+    }
+
+    /// Wins minus losses
+    pub fn net(&self) -> i32 {
+        self.record.wins - self.record.losses
+    }
+
+    /// `sessions` follow the report itself, newest first like `sessions` returns them, and `notes`
+    /// are listed last, one line each
+    pub fn to_markdown(
+        &self,
+        title: &str,
+        hero_name: impl Fn(i32) -> String,
+        facet_name: impl Fn(i32, i32) -> String,
+        sessions: &[Report],
+        notes: &[String],
+    ) -> String {
+        // A `|` in a hero or facet name would split the table cell
+        let hero_name = |id| hero_name(id).replace('|', r"\|");
+        let facet_name = |id, variant| facet_name(id, variant).replace('|', r"\|");
+        let mut markdown = format!("# {}\n\n", title);
+        markdown.push_str(&self.markdown_section(&hero_name, &facet_name));
+        for (index, session) in sessions.iter().enumerate() {
+            markdown.push_str(&format!("\n## {}\n\n", session.session_title(sessions.len() - index)));
+            markdown.push_str(&session.markdown_section(&hero_name, &facet_name));
+        }
+        if !notes.is_empty() {
+            markdown.push_str("\n## 笔记\n\n");
+            for note in notes {
+                markdown.push_str(&format!("- {}\n", note));
+            }
+        }
+        markdown
+    }
+
+    pub fn to_html(
+        &self,
+        title: &str,
+        hero_name: impl Fn(i32) -> String,
+        facet_name: impl Fn(i32, i32) -> String,
+        sessions: &[Report],
+        notes: &[String],
+    ) -> String {
+        let hero_name = |id| escape_html(&hero_name(id));
+        let facet_name = |id, variant| escape_html(&facet_name(id, variant));
+        let mut body = self.html_section(&hero_name, &facet_name);
+        for (index, session) in sessions.iter().enumerate() {
+            body.push_str(&format!("<h2>{}</h2>\n", escape_html(&session.session_title(sessions.len() - index))));
+            body.push_str(&session.html_section(&hero_name, &facet_name));
+        }
+        if !notes.is_empty() {
+            let items = notes.iter().map(|note| format!("<li>{}</li>\n", escape_html(note))).collect::<String>();
+            body.push_str(&format!("<h2>笔记</h2>\n<ul>\n{}</ul>\n", items));
+        }
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n{body}</body>\n</html>\n",
+            title = escape_html(title),
+        )
+    }
+
+    /// `Session 2 (20:15 - 23:40)`, in local time
+    pub fn session_title(
+        &self,
+        number: usize,
+    ) -> String {
+        format!("Session {} ({} - {})", number, clock_col(self.start_time), clock_col(self.end_time))
+    }
+
+    /// Summary list and hero table, names already escaped
+    fn markdown_section(
+        &self,
+        hero_name: &impl Fn(i32) -> String,
+        facet_name: &impl Fn(i32, i32) -> String,
+    ) -> String {
+        let mut markdown = String::new();
+        for (label, value) in self.summary(hero_name) {
+            markdown.push_str(&format!("- {}: {}\n", label, value));
+        }
+        markdown.push_str("\n| 英雄 | 场次 | 胜场 | 胜率 | KDA | 命石 |\n| --- | --- | --- | --- | --- | --- |\n");
+        for hero in &self.heroes {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {:.0}% | {:.2} | {} |\n",
+                hero_name(hero.hero_id),
+                hero.games,
+                hero.wins,
+                hero.win_rate() * 100.0,
+                hero.kda(),
+                hero.facets_col(facet_name)
+            ));
+        }
+        markdown
+    }
+
+    /// Summary list and hero table, names already escaped
+    fn html_section(
+        &self,
+        hero_name: &impl Fn(i32) -> String,
+        facet_name: &impl Fn(i32, i32) -> String,
+    ) -> String {
+        let summary = self
+            .summary(hero_name)
+            .into_iter()
+            .map(|(label, value)| format!("<li>{}: {}</li>\n", label, value))
+            .collect::<String>();
+        let heroes = self
+            .heroes
+            .iter()
+            .map(|hero| {
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.0}%</td><td>{:.2}</td><td>{}</td></tr>\n",
+                    hero_name(hero.hero_id),
+                    hero.games,
+                    hero.wins,
+                    hero.win_rate() * 100.0,
+                    hero.kda(),
+                    hero.facets_col(facet_name)
+                )
+            })
+            .collect::<String>();
+        format!(
+            "<ul>\n{summary}</ul>\n<table>\n\
+             <tr><th>英雄</th><th>场次</th><th>胜场</th><th>胜率</th><th>KDA</th><th>命石</th></tr>\n{heroes}</table>\n"
+        )
+    }
+
+    /// Label and value of every line above the hero table
+    pub fn summary(
+        &self,
+        hero_name: &impl Fn(i32) -> String,
+    ) -> Vec<(&'static str, String)> {
+        vec![
+            ("战绩", self.record.to_string()),
+            ("净胜", format!("{:+}", self.net())),
+            ("胜率", format!("{:.0}%", self.record.win_rate() * 100.0)),
+            ("游戏时长", time_played_col(self.time_played)),
+            ("最佳", self.best.describe(hero_name)),
+            ("最差", self.worst.describe(hero_name)),
+        ]
+    }
+}
+
+/// Play sessions, newest first. A session ends once no game starts within `SESSION_GAP` of the
+/// previous game's end.
+pub fn sessions<'a>(views: impl IntoIterator<Item = &'a MatchDetailView>) -> Vec<Report> {
+    session_groups(views).iter().rev().filter_map(|group| Report::new(group)).collect()
+}
+
+/// Games of each play session, oldest session and game first
+pub(crate) fn session_groups<'a>(views: impl IntoIterator<Item = &'a MatchDetailView>) -> Vec<Vec<&'a MatchDetailView>> {
+    let mut views = views.into_iter().collect::<Vec<_>>();
+    views.sort_by_key(|view| view.start_time());
+
+    let mut groups: Vec<Vec<&MatchDetailView>> = Vec::new();
+    for view in views {
+        match groups.last_mut() {
+            Some(group) if group.last().is_some_and(|last| view.start_time() - end_time(last) <= SESSION_GAP) => {
+                group.push(view);
+            }
+            _ => groups.push(vec![view]),
+        }
+    }
+    groups
+}
+
+/// One report per local calendar day a game started on, newest first
+pub fn days<'a>(views: impl IntoIterator<Item = &'a MatchDetailView>) -> Vec<(NaiveDate, Report)> {
+    let mut days: BTreeMap<NaiveDate, Vec<&MatchDetailView>> = BTreeMap::new();
+    for view in views {
+        if let Some(date) = view.start_date() {
+            days.entry(date).or_default().push(view);
+        }
+    }
+    days.into_iter()
+        .rev()
+        .filter_map(|(date, views)| Report::new(&views).map(|report| (date, report)))
+        .collect()
+}
+
+/// Unix seconds as `20:15` in local time
+pub fn clock_col(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| chrono::DateTime::<chrono::Local>::from(dt).format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// `seconds` as `2h13m`
+pub fn time_played_col(seconds: i64) -> String {
+    format!("{}h{}m", seconds / 3600, seconds % 3600 / 60)
+}
+
+pub(crate) fn end_time(view: &MatchDetailView) -> i64 {
+    view.start_time() + i64::from(view.duration())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::view;
+
+    #[test]
+    fn split_sessions_by_gap() {
+        let views = [
+            view(1, 0, true, 10),
+            view(2, 1800 + SESSION_GAP, false, 2),
+            view(3, 10 * SESSION_GAP, true, 5),
+        ];
+        let sessions = sessions(&views);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].record, Record { wins: 1, losses: 0 });
+        assert_eq!(sessions[1].record, Record { wins: 1, losses: 1 });
+        assert_eq!(sessions[1].net(), 0);
+        assert_eq!(sessions[1].time_played, 3600);
+        assert_eq!(sessions[1].best.match_id, 1);
+        assert_eq!(sessions[1].worst.match_id, 2);
+    }
+
+    #[test]
+    fn markdown_table() {
+        let views = [view(1, 0, true, 10)];
+        let report = Report::new(&[&views[0]]).unwrap();
+        let markdown = report.to_markdown(
+            "Today",
+            |_| String::from("Anti|Mage"),
+            |_, variant| format!("Facet {}", variant),
+            &[],
+            &[String::from("Throw")],
+        );
+
+        assert!(markdown.starts_with("# Today\n"));
+        assert!(markdown.contains("- 游戏时长: 0h30m\n"));
+        assert!(markdown.contains(r"| Anti\|Mage | 1 | 1 | 100% | 3.00 | Facet 1 1W 0L (100%) |"));
+        assert!(markdown.ends_with("## 笔记\n\n- Throw\n"));
+    }
+
+    #[test]
+    fn export_sessions() {
+        let views = [view(1, 0, true, 10), view(2, 10 * SESSION_GAP, false, 2)];
+        let day = Report::new(&[&views[0], &views[1]]).unwrap();
+        let sessions = sessions(&views);
+        let hero_name = |_| String::from("<Axe>");
+        let facet_name = |_, variant| format!("Facet {}", variant);
+
+        let markdown = day.to_markdown("Today", hero_name, facet_name, &sessions, &[]);
+        let session_2 = markdown.find("## Session 2 (").unwrap();
+        let session_1 = markdown.find("## Session 1 (").unwrap();
+        assert!(session_2 < session_1);
+        assert!(markdown[session_2..session_1].contains("- 战绩: 0W 1L\n"));
+        assert!(markdown[session_1..].contains("- 战绩: 1W 0L\n"));
+
+        let html = day.to_html("Today", hero_name, facet_name, &sessions, &[]);
+        assert_eq!(html.matches("<table>").count(), 3);
+        assert!(html.contains("<h2>Session 1 ("));
+        assert!(html.contains("<td>&lt;Axe&gt;</td>"));
+    }
+}
@@ -0,0 +1,128 @@
+//! Aggregates over many matches of the tracked account
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{data::matches::MatchDetailView, metrics};
+
+/// Wins and losses over some matches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Record {
+    pub wins: i32,
+    pub losses: i32,
+}
+
+impl Record {
+    pub fn of<'a>(views: impl IntoIterator<Item = &'a MatchDetailView>) -> Self {
+        views.into_iter().fold(Self::default(), |mut record, view| {
+            if view.win() {
+                record.wins += 1;
+            } else {
+                record.losses += 1;
+            }
+            record
+        })
+    }
+
+    pub fn games(&self) -> i32 {
+        self.wins + self.losses
+    }
+
+    pub fn win_rate(&self) -> f32 {
+        metrics::ratio(self.wins, self.games())
+    }
+}
+
+impl Display for Record {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{}W {}L", self.wins, self.losses)
+    }
+}
+
+/// Matches started on the local `date`
+pub fn played_on<'a>(
+    views: impl IntoIterator<Item = &'a MatchDetailView>,
+    date: NaiveDate,
+) -> impl Iterator<Item = &'a MatchDetailView> {
+    views.into_iter().filter(move |view| view.start_date() == Some(date))
+}
+
+/// The tracked account's record on one hero
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeroStats {
+    pub hero_id: i32,
+    pub games: i32,
+    pub wins: i32,
+    pub kills: i32,
+    pub deaths: i32,
+    pub assists: i32,
+    /// Record per `hero_variant`
+    pub facets: BTreeMap<i32, Record>,
+}
+
+impl HeroStats {
+    fn new(hero_id: i32) -> Self {
+        Self {
+            hero_id,
+            games: 0,
+            wins: 0,
+            kills: 0,
+            deaths: 0,
+            assists: 0,
+            facets: BTreeMap::new(),
+        }
+    }
+
+    pub fn win_rate(&self) -> f32 {
+        metrics::ratio(self.wins, self.games)
+    }
+
+    /// Over all games on the hero, not the mean of the per-game KDA
+    pub fn kda(&self) -> f32 {
+        metrics::kda(self.kills, self.deaths, self.assists)
+    }
+
+    /// `Facet 2W 1L (67%), ...`, for table cells and exports
+    pub fn facets_col(
+        &self,
+        facet_name: impl Fn(i32, i32) -> String,
+    ) -> String {
+        self.facets
+            .iter()
+            .map(|(variant, record)| format!("{} {} ({:.0}%)", facet_name(self.hero_id, *variant), record, record.win_rate() * 100.0))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Record per hero, most played first
+pub fn hero_stats<'a>(views: impl IntoIterator<Item = &'a MatchDetailView>) -> Vec<HeroStats> {
+    let mut heroes: HashMap<i32, HeroStats> = HashMap::new();
+    for view in views {
+        let player = view.player_detail();
+        let stats = heroes.entry(player.hero_id).or_insert_with(|| HeroStats::new(player.hero_id));
+        stats.games += 1;
+        stats.wins += i32::from(view.win());
+        stats.kills += player.kills;
+        stats.deaths += player.deaths;
+        stats.assists += player.assists;
+        let facet = stats.facets.entry(player.hero_variant).or_default();
+        if view.win() {
+            facet.wins += 1;
+        } else {
+            facet.losses += 1;
+        }
+    }
+
+    let mut heroes = heroes.into_values().collect::<Vec<_>>();
+    heroes.sort_by(|a, b| b.games.cmp(&a.games).then(b.wins.cmp(&a.wins)).then(a.hero_id.cmp(&b.hero_id)));
+    heroes
+}
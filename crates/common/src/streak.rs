@@ -0,0 +1,138 @@
+//! Win/loss streaks and tilt detection over the tracked account's matches
+
+use serde::Serialize;
+
+use crate::{
+    data::matches::MatchDetailView,
+    metrics,
+    report::{end_time, session_groups, SESSION_GAP},
+};
+
+/// Consecutive games with the same result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Streak {
+    pub win: bool,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Streaks {
+    /// Streak the latest game is part of
+    pub current: Option<Streak>,
+    pub longest_win: usize,
+    pub longest_loss: usize,
+}
+
+impl Streaks {
+    pub fn new<'a>(views: impl IntoIterator<Item = &'a MatchDetailView>) -> Self {
+        let mut views = views.into_iter().collect::<Vec<_>>();
+        views.sort_by_key(|view| view.start_time());
+
+        let mut streaks = Self::default();
+        for view in views {
+            let current = match streaks.current {
+                Some(streak) if streak.win == view.win() => Streak {
+                    length: streak.length + 1,
+                    ..streak
+                },
+                _ => Streak { win: view.win(), length: 1 },
+            };
+            let longest = if current.win {
+                &mut streaks.longest_win
+            } else {
+                &mut streaks.longest_loss
+            };
+            *longest = (*longest).max(current.length);
+            streaks.current = Some(current);
+        }
+        streaks
+    }
+}
+
+/// Losing in a row in the ongoing session while playing worse and worse
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Tilt {
+    pub losses: usize,
+    /// KDA of the first and the latest loss in the streak
+    pub kda: (f32, f32),
+    /// GPM of the first and the latest loss in the streak
+    pub gold_per_min: (i32, i32),
+    /// The latest game, a reminder dismissed for it stays dismissed
+    pub match_id: i64,
+}
+
+/// `Some` when the session still going at `now` (unix seconds) ends with at least `threshold`
+/// losses, never fewer than 2, and both KDA and GPM dropped from the first to the latest of them
+pub fn tilt<'a>(
+    views: impl IntoIterator<Item = &'a MatchDetailView>,
+    threshold: usize,
+    now: i64,
+) -> Option<Tilt> {
+    let session = session_groups(views).pop()?;
+    let latest = *session.last()?;
+    if now - end_time(latest) > SESSION_GAP {
+        return None;
+    }
+
+    let losses = session.iter().rev().take_while(|view| !view.win()).collect::<Vec<_>>();
+    if losses.len() < threshold.max(2) {
+        return None;
+    }
+
+    let kda = |view: &MatchDetailView| {
+        let player = view.player_detail();
+        metrics::kda(player.kills, player.deaths, player.assists)
+    };
+    let first = losses[losses.len() - 1];
+    let tilt = Tilt {
+        losses: losses.len(),
+        kda: (kda(first), kda(latest)),
+        gold_per_min: (first.player_detail().gold_per_min, latest.player_detail().gold_per_min),
+        match_id: latest.match_id(),
+    };
+    (tilt.kda.1 < tilt.kda.0 && tilt.gold_per_min.1 < tilt.gold_per_min.0).then_some(tilt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::view;
+
+    #[test]
+    fn current_and_longest() {
+        let results = [true, true, true, false, false, true, false, false];
+        let views = results
+            .iter()
+            .enumerate()
+            .map(|(i, win)| view(i as i64, i as i64 * 3600, *win, 5))
+            .collect::<Vec<_>>();
+        let streaks = Streaks::new(&views);
+
+        assert_eq!(streaks.current, Some(Streak { win: false, length: 2 }));
+        assert_eq!(streaks.longest_win, 3);
+        assert_eq!(streaks.longest_loss, 2);
+    }
+
+    #[test]
+    fn tilt_needs_falling_performance() {
+        let views = [
+            view(1, 0, true, 10),
+            view(2, 3600, false, 8),
+            view(3, 7200, false, 6),
+            view(4, 10800, false, 2),
+        ];
+        let now = 10800 + 1800;
+
+        let tilted = tilt(&views, 3, now).unwrap();
+        assert_eq!(tilted.losses, 3);
+        assert_eq!(tilted.gold_per_min, (560, 440));
+        assert_eq!(tilted.match_id, 4);
+
+        // The session is over
+        assert_eq!(tilt(&views, 3, now + 2 * SESSION_GAP), None);
+        assert_eq!(tilt(&views, 4, now), None);
+
+        let recovering = [view(1, 0, false, 2), view(2, 3600, false, 5), view(3, 7200, false, 8)];
+        assert_eq!(tilt(&recovering, 3, 7200 + 1800), None);
+    }
+}
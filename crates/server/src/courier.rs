@@ -1,57 +1,152 @@
 use common::data::{
-    constant::{ConstantRequest, ConstantResponse},
-    matches::{MatchDetailResponse, MatchHistoryResponse},
+    constant::{ConstantLanguage, ConstantRequest, ConstantResponse},
+    matches::{MatchDetail, MatchDetailResponse, MatchHistoryResponse},
+    opendota::OpenDotaMatch,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
+use tracing::debug;
 
-use crate::error::{DataFormatSnafu, NoneValueSnafu, SteamApiSnafu};
+use crate::error::{DataFormatSnafu, DownloadSnafu, NoneValueSnafu, OpenDotaSnafu, PrivateProfileSnafu, SteamApiSnafu};
 
-const IDOTA2MATCH: &str = "https://api.steampowered.com/IDOTA2Match_570";
+pub const STEAM_API: &str = "https://api.steampowered.com";
+const IDOTA2MATCH: &str = "IDOTA2Match_570";
 const STRATZ_API: &str = "https://api.stratz.com/graphql";
+const OPENDOTA_API: &str = "https://api.opendota.com/api";
 
 pub struct Courier {
     client: reqwest::Client,
+    /// Host of the Steam Web API, a mirror can stand in where the official one is unreachable
+    steam_api: String,
 }
 
 impl Default for Courier {
     fn default() -> Self {
+        Self::with_steam_api(STEAM_API)
+    }
+}
+
+impl Courier {
+    pub fn with_steam_api(steam_api: impl Into<String>) -> Self {
         Self {
             client: reqwest::Client::new(),
+            steam_api: steam_api.into().trim_end_matches('/').to_owned(),
         }
     }
-}
 
-impl Courier {
     // matches
+    /// The account's latest match, empty for an account without any match yet
     pub async fn latest_match_detail(
         &self,
         key: &str,
         account_id: i64,
-    ) -> Result<MatchDetailResponse, crate::Error> {
-        let match_history_response = self.get_match_history(key, account_id, 1).await?;
-        let seq_num = match_history_response.match_seq_num();
-        let seq_num = seq_num.first().context(NoneValueSnafu { expected: "match_seq_num" })?;
+    ) -> Result<Vec<MatchDetail>, crate::Error> {
+        let match_history_response = self.get_match_history(key, account_id, None, 1).await?;
+        let Some(seq_num) = match_history_response.match_seq_num().first().copied() else {
+            return Ok(Vec::new());
+        };
+
+        let match_detail_response = self.get_match_detail(key, seq_num, 1).await?;
+        ensure_listed(account_id, match_detail_response.matches())?;
+        Ok(match_detail_response.into_matches())
+    }
+
+    /// One page of the account's match history, newest first, starting at `start_at_match_id`
+    /// (inclusive) or at the latest match when `None`.
+    ///
+    /// Every match costs an extra `GetMatchHistoryBySequenceNum` request, keep the pages small.
+    pub async fn match_history(
+        &self,
+        key: &str,
+        account_id: i64,
+        start_at_match_id: Option<i64>,
+        matches_requested: i32,
+    ) -> Result<Vec<MatchDetail>, crate::Error> {
+        let match_history_response = self.get_match_history(key, account_id, start_at_match_id, matches_requested).await?;
+
+        let mut matches = Vec::new();
+        for seq_num in match_history_response.match_seq_num() {
+            let match_detail = self
+                .get_match_detail(key, seq_num, 1)
+                .await?
+                .into_matches()
+                .into_iter()
+                .find(|m| m.match_seq_num() == seq_num)
+                .context(NoneValueSnafu { expected: "MatchDetail" })?;
+            matches.push(match_detail);
+        }
+        ensure_listed(account_id, &matches)?;
+        Ok(matches)
+    }
+
+    /// Any match by its id, whoever played in it. The Steam API can only look matches up by
+    /// sequence number: it is found in `account_id`'s history when they played in the match, and
+    /// asked from OpenDota otherwise.
+    pub async fn match_by_id(
+        &self,
+        key: &str,
+        account_id: i64,
+        match_id: i64,
+    ) -> Result<MatchDetail, crate::Error> {
+        let history = self.get_match_history(key, account_id, Some(match_id), 1).await;
+        let seq_num = match history.ok().and_then(|history| history.match_seq_num_of(match_id)) {
+            Some(seq_num) => seq_num,
+            None => {
+                debug!("Match {} is not in the history of {}, asking OpenDota", match_id, account_id);
+                self.opendota_match(match_id)
+                    .await?
+                    .match_seq_num
+                    .context(NoneValueSnafu { expected: "match_seq_num" })?
+            }
+        };
 
-        let match_detail_response = self.get_match_detail(key, *seq_num, 1).await?;
-        Ok(match_detail_response)
+        self.get_match_detail(key, seq_num, 1)
+            .await?
+            .into_matches()
+            .into_iter()
+            .find(|m| m.match_id() == match_id)
+            .context(NoneValueSnafu { expected: "MatchDetail" })
+    }
+
+    async fn opendota_match(
+        &self,
+        match_id: i64,
+    ) -> Result<OpenDotaMatch, crate::Error> {
+        let url = format!("{}/matches/{}", OPENDOTA_API, match_id);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .context(OpenDotaSnafu { url })?;
+        let response = response
+            .json::<OpenDotaMatch>()
+            .await
+            .context(DataFormatSnafu { data: "OpenDotaMatch" })?;
+        Ok(response)
     }
 
     async fn get_match_history(
         &self,
         key: &str,
         account_id: i64,
+        start_at_match_id: Option<i64>,
         matches_requested: i32,
     ) -> Result<MatchHistoryResponse, crate::Error> {
-        let url = format!(
-            "{}/GetMatchHistory/v1?key={}&account_id={}&matches_requested={}",
-            IDOTA2MATCH, key, account_id, matches_requested
+        let mut url = format!(
+            "{}/{}/GetMatchHistory/v1?key={}&account_id={}&matches_requested={}",
+            self.steam_api, IDOTA2MATCH, key, account_id, matches_requested
         );
+        if let Some(match_id) = start_at_match_id {
+            url.push_str(&format!("&start_at_match_id={}", match_id));
+        }
         let response = self.client.get(&url).send().await.context(SteamApiSnafu {
             entrypoint: "GetMatchHistory",
         })?;
         let response = response.json::<MatchHistoryResponse>().await.context(DataFormatSnafu {
             data: "MatchHistoryResponse",
         })?;
+        ensure!(!response.is_not_allowed(), PrivateProfileSnafu { account_id });
         Ok(response)
     }
 
@@ -64,8 +159,8 @@ impl Courier {
         matches_requested: i32,
     ) -> Result<MatchDetailResponse, crate::Error> {
         let url = format!(
-            "{}/GetMatchHistoryBySequenceNum/v1?key={}&start_at_match_seq_num={}&matches_requested={}",
-            IDOTA2MATCH, key, sequence, matches_requested
+            "{}/{}/GetMatchHistoryBySequenceNum/v1?key={}&start_at_match_seq_num={}&matches_requested={}",
+            self.steam_api, IDOTA2MATCH, key, sequence, matches_requested
         );
 
         let response = self.client.get(&url).send().await.context(SteamApiSnafu {
@@ -84,8 +179,9 @@ impl Courier {
     pub async fn constant(
         &self,
         key: &str,
+        language: ConstantLanguage,
     ) -> Result<ConstantResponse, crate::Error> {
-        let request = ConstantRequest::default();
+        let request = ConstantRequest::new(language);
 
         let response = self
             .client
@@ -104,6 +200,34 @@ impl Courier {
 
         Ok(response)
     }
+
+    /// Raw bytes of a static asset, e.g. an icon on the Valve CDN
+    pub async fn download(
+        &self,
+        url: &str,
+    ) -> Result<Vec<u8>, crate::Error> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .context(DownloadSnafu { url })?;
+        let bytes = response.bytes().await.context(DataFormatSnafu { data: "bytes" })?;
+        Ok(bytes.to_vec())
+    }
+}
+
+/// A private account still gets its history listed, but shows up anonymized in the matches
+fn ensure_listed(
+    account_id: i64,
+    matches: &[MatchDetail],
+) -> Result<(), crate::Error> {
+    let anonymized = matches
+        .iter()
+        .any(|m| m.player_index_of(account_id).is_none() && m.has_anonymous_player());
+    ensure!(!anonymized, PrivateProfileSnafu { account_id });
+    Ok(())
 }
 
 #[cfg(test)]
@@ -117,8 +241,7 @@ mod tests {
         println!("account_id: {}", account_id);
         println!("key: {}", key);
 
-        let client = reqwest::Client::new();
-        let courier = super::Courier { client };
+        let courier = super::Courier::default();
         let response = courier.latest_match_detail(&key, account_id).await.unwrap();
         println!("{:#?}", response);
     }
@@ -128,9 +251,8 @@ mod tests {
         dotenvy::dotenv().ok();
         let key = std::env::var("STRATZ_KEY").unwrap();
 
-        let client = reqwest::Client::new();
-        let courier = super::Courier { client };
-        let response = courier.constant(&key).await.unwrap();
+        let courier = super::Courier::default();
+        let response = courier.constant(&key, Default::default()).await.unwrap();
         println!("{:#?}", response);
     }
 }
@@ -13,6 +13,22 @@ pub enum Error {
         #[snafu(source)]
         error: reqwest::Error,
     },
+    #[snafu(display("OpenDota: Error when requesting to: {}", url))]
+    OpenDota {
+        url: String,
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: reqwest::Error,
+    },
+    #[snafu(display("Download: Error when downloading: {}", url))]
+    Download {
+        url: String,
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: reqwest::Error,
+    },
     #[snafu(display("DataFormat: Invalid data format when trying to parse response to {}", data))]
     DataFormat {
         data: String,
@@ -21,6 +37,12 @@ pub enum Error {
         #[snafu(source)]
         error: reqwest::Error,
     },
+    #[snafu(display("PrivateProfile: match data of account {} is not public", account_id))]
+    PrivateProfile {
+        account_id: i64,
+        #[snafu(implicit)]
+        location: Location,
+    },
     #[snafu(display("NoneValue: expected {} but found None", expected))]
     NoneValue {
         expected: String,
@@ -28,3 +50,10 @@ pub enum Error {
         location: Location,
     },
 }
+
+impl Error {
+    /// The account has "Expose Public Match Data" turned off in the Dota client
+    pub fn is_private_profile(&self) -> bool {
+        matches!(self, Error::PrivateProfile { .. })
+    }
+}